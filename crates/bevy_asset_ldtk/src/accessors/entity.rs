@@ -1,6 +1,6 @@
 // Copyright 2025 Natalie Baker // AGPLv3 //
 
-use bevy::math::IVec2;
+use bevy::math::{IVec2, Vec2};
 
 use crate::schema as schema;
 
@@ -41,9 +41,78 @@ impl LdtkEntity<'_> {
         )
     }
 
+    /// Where the pivot sits within the entity, from (0, 0) top-left to (1, 1) bottom-right as in the editor
+    #[must_use]
+    pub fn pivot(&self) -> Vec2 {
+        Vec2::new(
+            self.entity.pivot.first().copied().unwrap_or_default() as f32,
+            self.entity.pivot.get(1).copied().unwrap_or_default() as f32,
+        )
+    }
+
+    #[must_use]
+    pub const fn size_px(&self) -> IVec2 {
+        IVec2::new(
+            self.entity.width  as i32,
+            self.entity.height as i32,
+        )
+    }
+
+    #[must_use]
+    pub fn pivot_px(&self) -> IVec2 {
+        self.layer.offset_px() + self.pivot_local_px()
+    }
+
+    /// The pivot's position, with y flipped upwards as [`LdtkEntity::offset_local_px`]
+    #[must_use]
+    pub fn pivot_local_px(&self) -> IVec2 {
+        IVec2::new(
+            self.entity.px[0] as i32,
+            self.layer.size_px().y - self.entity.px[1] as i32
+        )
+    }
+
     #[must_use]
     pub const fn layer(&self) -> &LdtkLayer {
         self.layer
     }
 
 }
+
+impl LdtkEntity<'_> {
+
+    #[must_use]
+    pub fn field(&self, identifier: &str) -> Option<&schema::FieldInstance> {
+        self.entity.field_instances.iter().find(|v| v.identifier == identifier)
+    }
+
+    /// Values of an enum, or enum array, field. Unset values are skipped.
+    pub fn field_enums(&self, identifier: &str) -> impl Iterator<Item = &str> {
+        let value = self.field(identifier).and_then(|v| v.value.as_ref());
+        let values = match value {
+            Some(serde_json::Value::Array(values)) => values.as_slice(),
+            Some(value) => core::slice::from_ref(value),
+            None => &[],
+        };
+        values.iter().filter_map(serde_json::Value::as_str)
+    }
+
+    /// Values of a point, or point array, field as pixel positions at the center of each cell,
+    /// with y flipped upwards as [`LdtkEntity::offset_px`]. Unset points are skipped.
+    #[must_use]
+    pub fn field_points_px(&self, identifier: &str) -> Vec<IVec2> {
+        let Some(value) = self.field(identifier).and_then(|v| v.value.clone()) else { return Vec::new(); };
+        let points = if value.is_array() {
+            serde_json::from_value::<Vec<Option<schema::GridPoint>>>(value).unwrap_or_default()
+        } else {
+            vec![serde_json::from_value::<schema::GridPoint>(value).ok()]
+        };
+
+        let grid = self.layer.size_grid();
+        points.into_iter().flatten().map(|v| self.layer.offset_px() + IVec2::new(
+            (v.cx as i32)*grid + grid/2,
+            self.layer.size_px().y - (v.cy as i32)*grid - grid/2,
+        )).collect()
+    }
+
+}
@@ -48,4 +48,18 @@ impl LdtkRoot<'_> {
         self.0.defs.tilesets.len()
     }
 
-}
\ No newline at end of file
+}
+
+impl LdtkRoot<'_> {
+
+    /// Internal and external enum definitions
+    pub fn enums(&self) -> impl Iterator<Item = &schema::EnumDefinition> {
+        self.0.defs.enums.iter().chain(self.0.defs.external_enums.iter())
+    }
+
+    #[must_use]
+    pub fn get_enum(&self, identifier: &str) -> Option<&schema::EnumDefinition> {
+        self.enums().find(|v| v.identifier == identifier)
+    }
+
+}
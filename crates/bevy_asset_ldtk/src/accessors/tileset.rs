@@ -62,6 +62,15 @@ impl LdtkTileset<'_> {
         self.tileset.enum_tags.iter().find(|v| v.enum_value_id == tag).is_some_and(|v| v.tile_ids.contains(&id))
     }
 
+    pub fn enum_tags(&self, id: i64) -> impl Iterator<Item = &str> {
+        self.tileset.enum_tags.iter().filter(move |v| v.tile_ids.contains(&id)).map(|v| v.enum_value_id.as_str())
+    }
+
+    #[must_use]
+    pub fn custom_data(&self, id: i64) -> Option<&str> {
+        self.tileset.custom_data.iter().find(|v| v.tile_id == id).map(|v| v.data.as_str())
+    }
+
 }
 
 impl LdtkTileset<'_> {
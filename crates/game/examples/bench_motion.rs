@@ -0,0 +1,114 @@
+// Copyright 2025 Natalie Baker // AGPLv3 //
+
+// Compares the old per-iteration grid walk against MotionQueryContext.
+// Run with `cargo run --release -p game --example bench_motion`
+
+use std::time::{Duration, Instant};
+
+use bevy::prelude::*;
+use game::{collision::{CollisionFilter, CollisionMap, MotionQueryContext}, pawn::solve_motion};
+use raydee::prelude::*;
+
+const MAP_SIZE:    i32   = 64;
+const PAWN_COUNT:  usize = 256;
+const FRAME_COUNT: usize = 600;
+const SPEED:       f32   = 0.05;
+const SKIN:        f32   = 1e-4;
+const FILTER:      CollisionFilter = CollisionFilter::DEFAULT;
+const ITERATIONS:  usize = 4;
+
+fn main() {
+    let map = build_map();
+    let collider = Circle::new(0.5);
+
+    let (uncached, checksum_uncached) = run(|_, origin, target| {
+        solve_motion_uncached(&map, collider, origin, target)
+    });
+
+    let (per_move, checksum_per_move) = run(|_, origin, target| {
+        solve_motion(ITERATIONS, SKIN, &map, FILTER, collider, origin, target, None, &mut MotionQueryContext::default(), None)
+    });
+
+    let mut contexts: Vec<MotionQueryContext> = (0..PAWN_COUNT).map(|_| MotionQueryContext::default()).collect();
+    let (persistent, checksum_persistent) = run(|i, origin, target| {
+        solve_motion(ITERATIONS, SKIN, &map, FILTER, collider, origin, target, None, &mut contexts[i], None)
+    });
+
+    println!("{PAWN_COUNT} pawns x {FRAME_COUNT} frames on a {MAP_SIZE}x{MAP_SIZE} tile map");
+    report("uncached grid walk", uncached,   uncached, checksum_uncached  );
+    report("per-move context",   per_move,   uncached, checksum_per_move  );
+    report("persistent context", persistent, uncached, checksum_persistent);
+}
+
+fn report(name: &str, time: Duration, baseline: Duration, checksum: Vec2) {
+    let speedup = baseline.as_secs_f64() / time.as_secs_f64();
+    println!("{name:>20}: {:>9.3}ms ({speedup:.2}x) checksum {checksum}", time.as_secs_f64()*1000.0);
+}
+
+fn run(mut solve: impl FnMut(usize, Vec2, Vec2) -> MotionFrame) -> (Duration, Vec2) {
+    let mut rng = 0x2545_F491_u32;
+    let mut pawns: Vec<(Vec2, Vec2)> = (0..PAWN_COUNT).map(|_| {
+        let cell = IVec2::new(
+            1 + (next_random(&mut rng) % (MAP_SIZE as u32 - 2)) as i32,
+            1 + (next_random(&mut rng) % (MAP_SIZE as u32 - 2)) as i32,
+        );
+        let angle = (next_random(&mut rng) % 360) as f32;
+        (cell.as_vec2() + 0.5, Vec2::from_angle(angle.to_radians()))
+    }).collect();
+
+    let turn  = Vec2::from_angle(0.01);
+    let start = Instant::now();
+    for _ in 0..FRAME_COUNT {
+        for (i, (origin, direction)) in pawns.iter_mut().enumerate() {
+            *origin    = solve(i, *origin, *origin + *direction * SPEED).position_start();
+            *direction = direction.rotate(turn);
+        }
+    }
+    let elapsed = start.elapsed();
+
+    (elapsed, pawns.iter().map(|(origin, _)| *origin).sum())
+}
+
+fn build_map() -> CollisionMap {
+    let mut map = CollisionMap::default();
+    for x in 0..MAP_SIZE {
+        for y in 0..MAP_SIZE {
+            let border = x == 0 || y == 0 || x == MAP_SIZE-1 || y == MAP_SIZE-1;
+            let pillar = x % 4 == 0 && y % 4 == 0;
+            if border || pillar {
+                map.insert(IVec2::new(x, y).as_vec2() + 0.5, Rectangle::from_size(Vec2::ONE), FILTER, None);
+            }
+        }
+    }
+    map
+}
+
+// The solver as it was before MotionQueryContext, walking the grid every iteration
+fn solve_motion_uncached(map: &CollisionMap, collider: Circle, origin: Vec2, target: Vec2) -> MotionFrame {
+    let mut curr_motion = MotionFrame::new_from_target(collider, origin, target);
+    let mut iter_remaining = ITERATIONS;
+
+    while curr_motion.distance() > 0.0 && iter_remaining > 0 {
+        iter_remaining -= 1;
+
+        let bbox  = curr_motion.collider().bounding_box();
+        let start = curr_motion.position_start();
+        let end   = curr_motion.position_end();
+
+        let mut query = MotionQuery::new(curr_motion, SKIN);
+        map.visit(start.min(end)+bbox[0], start.max(end)+bbox[1], |v| v.iter().for_each(|e| { query.test(e.origin, &e.collider); }));
+
+        let mut next_motion = query.result();
+        next_motion.set_distance(next_motion.distance() * Vec2::dot(curr_motion.direction(), next_motion.direction()).abs());
+        curr_motion = next_motion;
+    }
+
+    curr_motion
+}
+
+fn next_random(state: &mut u32) -> u32 {
+    *state ^= *state << 13;
+    *state ^= *state >> 17;
+    *state ^= *state << 5;
+    *state
+}
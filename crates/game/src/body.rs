@@ -0,0 +1,265 @@
+// Copyright 2025 Natalie Baker // AGPLv3 //
+
+use bevy::prelude::*;
+use raydee::prelude::*;
+
+use crate::{collision::{CollisionFilter, CollisionMap, MotionQueryContext}, pawn::{solve_motion, PawnTransformSettings}, scale::PixelsPerUnit};
+
+pub struct PluginBody;
+
+impl Plugin for PluginBody {
+    fn build(&self, app: &mut App) {
+        app
+            .add_systems(FixedFirst, body_store_previous_origin)
+            .add_systems(FixedUpdate, body_integrate)
+            .add_systems(PostUpdate, (body_collider_sync, sync_body_transform));
+    }
+}
+
+const BODY_SOLVE_ITERATIONS: usize = 4;
+const BODY_SKIN_DISTANCE:    f32   = 1e-4;
+
+/// How many bodies deep a push can propagate, ie. a crate pushing a row of crates
+const BODY_PUSH_DEPTH: usize = 8;
+
+/// How many times a body will push and re-solve its motion in a single move
+const BODY_PUSH_ATTEMPTS: usize = 2;
+
+/// A pushable collider, ie. a crate, moved by its velocity or by whatever runs into it.
+///
+/// Pawns push bodies lighter than themselves, bodies push any body in their way, and
+/// everything stops against static geometry.
+#[derive(Debug, Component)]
+pub struct DynamicBody {
+    collider: ShapeMoving,
+    origin:   Vec2,
+    origin_previous: Vec2,
+    filter:   CollisionFilter,
+    mass:     f32,
+    velocity: Vec2,
+    damping:  f32,
+    context:  MotionQueryContext,
+}
+
+impl DynamicBody {
+
+    pub const DEFAULT_MASS:    f32 = 0.5;
+    pub const DEFAULT_DAMPING: f32 = 4.0;
+
+    /// Speed below which a body comes to rest
+    pub const REST_SPEED: f32 = 0.01;
+
+    #[must_use]
+    pub fn new(origin: Vec2, collider: impl Into<ShapeMoving>, filter: CollisionFilter) -> Self {
+        Self {
+            collider: collider.into(),
+            origin,
+            origin_previous: origin,
+            filter,
+            mass: Self::DEFAULT_MASS,
+            velocity: Vec2::ZERO,
+            damping: Self::DEFAULT_DAMPING,
+            context: MotionQueryContext::default(),
+        }
+    }
+
+    #[must_use]
+    pub const fn with_mass(mut self, mass: f32) -> Self {
+        self.mass = mass;
+        self
+    }
+
+    /// Exponential velocity decay per second, zero slides forever
+    #[must_use]
+    pub const fn with_damping(mut self, damping: f32) -> Self {
+        self.damping = damping;
+        self
+    }
+
+    #[must_use]
+    pub const fn collider(&self) -> ShapeMoving {
+        self.collider
+    }
+
+    #[must_use]
+    pub const fn origin(&self) -> Vec2 {
+        self.origin
+    }
+
+    /// Teleports the body, ignoring collisions, without rendering it sliding there
+    pub const fn set_origin(&mut self, v: Vec2) {
+        self.origin = v;
+        self.origin_previous = v;
+    }
+
+    /// The origin at the start of the current simulation tick
+    #[must_use]
+    pub const fn origin_previous(&self) -> Vec2 {
+        self.origin_previous
+    }
+
+    #[must_use]
+    pub const fn filter(&self) -> CollisionFilter {
+        self.filter
+    }
+
+    pub const fn set_filter(&mut self, v: CollisionFilter) {
+        self.filter = v;
+    }
+
+    #[must_use]
+    pub const fn mass(&self) -> f32 {
+        self.mass
+    }
+
+    pub const fn set_mass(&mut self, v: f32) {
+        self.mass = v;
+    }
+
+    #[must_use]
+    pub const fn velocity(&self) -> Vec2 {
+        self.velocity
+    }
+
+    pub const fn set_velocity(&mut self, v: Vec2) {
+        self.velocity = v;
+    }
+
+    #[must_use]
+    pub const fn damping(&self) -> f32 {
+        self.damping
+    }
+
+    pub const fn set_damping(&mut self, v: f32) {
+        self.damping = v;
+    }
+
+    pub fn apply_impulse(&mut self, impulse: Vec2) {
+        self.velocity += impulse/self.mass;
+    }
+
+    /// The cached solve from the body's last move, including its contacts
+    #[must_use]
+    pub const fn context(&self) -> &MotionQueryContext {
+        &self.context
+    }
+
+}
+
+/// Moves the body by `delta`, pushing any bodies in its way, and returns how far it actually moved
+pub fn push_body(
+    entity: Entity,
+    delta: Vec2,
+    depth: usize,
+    q_bodies: &mut Query<&mut DynamicBody>,
+    colliders: &mut CollisionMap,
+) -> Vec2 {
+    let Ok(body) = q_bodies.get(entity) else { return Vec2::ZERO; };
+    let start  = body.origin;
+    let target = start + delta;
+
+    for attempt in 0..=BODY_PUSH_ATTEMPTS {
+        let Ok(mut body) = q_bodies.get_mut(entity) else { return Vec2::ZERO; };
+        let body = &mut *body;
+        solve_motion(BODY_SOLVE_ITERATIONS, BODY_SKIN_DISTANCE, colliders, body.filter, body.collider, start, target, Some(entity), &mut body.context, None);
+
+        if attempt == BODY_PUSH_ATTEMPTS || depth == BODY_PUSH_DEPTH {
+            break;
+        }
+
+        // Only bodies are pushed along, pawns and platforms block
+        let pushes = body.context.contacts().iter().filter_map(|v| {
+            let other = v.entry.entity.filter(|_| v.entry.dynamic)?;
+            Some((other, v.push_towards(target)?))
+        }).collect::<Vec<_>>();
+        let Some((other, push)) = pushes.into_iter().find(|v| q_bodies.contains(v.0)) else { break; };
+
+        if push_body(other, push, depth + 1, q_bodies, colliders) == Vec2::ZERO {
+            break;
+        }
+    }
+
+    // Registered right away, so body_collider_sync doesn't need to see the change
+    let Ok(mut body) = q_bodies.get_mut(entity) else { return Vec2::ZERO; };
+    let body = body.bypass_change_detection();
+    body.origin = body.context.path().last().copied().unwrap_or(start);
+    register_body_collider(colliders, entity, body);
+    body.origin - start
+}
+
+pub fn register_body_collider(colliders: &mut CollisionMap, entity: Entity, body: &DynamicBody) {
+    colliders.remove_entity(entity);
+    colliders.insert_dynamic(body.origin, body.collider, body.filter, Some(entity));
+}
+
+pub fn body_store_previous_origin(mut q_bodies: Query<&mut DynamicBody>) {
+    q_bodies.iter_mut().for_each(|mut b| {
+        let b = b.bypass_change_detection();
+        b.origin_previous = b.origin;
+    });
+}
+
+/// Moves bodies by their velocity, losing any velocity into the surfaces they hit
+pub fn body_integrate(
+    mut r_collision_map: ResMut<CollisionMap>,
+    r_time: Res<Time>,
+    q_entities: Query<Entity, With<DynamicBody>>,
+    mut q_bodies: Query<&mut DynamicBody>,
+) {
+    let dt = r_time.delta_secs();
+    for entity in &q_entities {
+        let Ok(body) = q_bodies.get(entity) else { continue; };
+        if body.velocity == Vec2::ZERO {
+            continue;
+        }
+
+        push_body(entity, body.velocity*dt, 0, &mut q_bodies, &mut r_collision_map);
+
+        let Ok(mut body) = q_bodies.get_mut(entity) else { continue; };
+        let mut velocity = body.context.contacts().iter().fold(body.velocity, |v, contact| {
+            v - contact.hit.normal*v.dot(contact.hit.normal).min(0.0)
+        });
+        velocity *= (-body.damping*dt).exp();
+        body.velocity = if velocity.length() < DynamicBody::REST_SPEED { Vec2::ZERO } else { velocity };
+    }
+}
+
+/// Keeps bodies registered as dynamic entries, for those spawned, despawned or moved outside [`push_body`]
+pub fn body_collider_sync(
+    mut r_collision_map: ResMut<CollisionMap>,
+    mut l_generation: Local<u64>,
+
+    q_bodies: Query<(Entity, &DynamicBody)>,
+    q_changed: Query<(Entity, &DynamicBody), Changed<DynamicBody>>,
+
+    mut ev_removed: RemovedComponents<DynamicBody>,
+) {
+    for entity in ev_removed.read() {
+        r_collision_map.remove_entity(entity);
+    }
+
+    // The map was cleared (ie. a level reload), everything needs re-registering
+    if *l_generation == r_collision_map.generation() {
+        q_changed.iter().for_each(|(e, b)| register_body_collider(&mut r_collision_map, e, b));
+    } else {
+        *l_generation = r_collision_map.generation();
+        q_bodies.iter().for_each(|(e, b)| register_body_collider(&mut r_collision_map, e, b));
+    }
+}
+
+/// Places bodies between their last two ticks' origins, the same as pawns, see [`crate::pawn::sync_pawn_transform`]
+pub fn sync_body_transform(
+    mut q_bodies: Query<(&mut Transform, &DynamicBody)>,
+    r_time: Res<Time<Fixed>>,
+    r_settings: Option<Res<PawnTransformSettings>>,
+    r_ppu: Option<Res<PixelsPerUnit>>,
+) {
+    let settings = r_settings.map_or_else(PawnTransformSettings::default, |v| *v);
+    let alpha    = r_time.overstep_fraction();
+    let ppu      = r_ppu.map(|v| *v);
+
+    q_bodies.iter_mut().for_each(|(mut t, b)| {
+        let origin = settings.render_origin(b.origin_previous(), b.origin(), alpha, ppu);
+        t.translation = origin.extend(t.translation.z);
+    });
+}
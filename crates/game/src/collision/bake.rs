@@ -0,0 +1,233 @@
+// Copyright 2025 Natalie Baker // AGPLv3 //
+
+use core::fmt::Display;
+
+use bevy::{math::{IVec2, UVec2, Vec2}, prelude::Rectangle};
+
+use super::{CollisionFilter, CollisionMap, CollisionMapID};
+
+/// Solid cells of a tile layer, to be baked into merged rectangles for the [`CollisionMap`]
+/// rather than inserting one entry per tile.
+///
+/// ```
+/// use bevy::math::{IVec2, UVec2};
+/// use game::collision::{CollisionBakedRect, CollisionGrid};
+///
+/// // ###.
+/// // ###.
+/// // #...
+/// let grid = CollisionGrid::from_cells([
+///     IVec2::new(0, 0), IVec2::new(1, 0), IVec2::new(2, 0),
+///     IVec2::new(0, 1), IVec2::new(1, 1), IVec2::new(2, 1),
+///     IVec2::new(0, 2),
+/// ]);
+///
+/// assert_eq!(grid.count(), 7);
+/// assert_eq!(grid.bake(), [
+///     CollisionBakedRect{min: IVec2::new(0, 0), size: UVec2::new(3, 2)},
+///     CollisionBakedRect{min: IVec2::new(0, 2), size: UVec2::new(1, 1)},
+/// ]);
+///
+/// // Split into 2x2 chunks, the top rectangle is cut in two at x = 2
+/// assert_eq!(grid.bake_chunked(UVec2::splat(2)).len(), 3);
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct CollisionGrid {
+    min:   IVec2,
+    size:  UVec2,
+    cells: Vec<bool>,
+}
+
+impl CollisionGrid {
+
+    #[must_use]
+    pub fn new(min: IVec2, size: UVec2) -> Self {
+        Self {
+            min,
+            size,
+            cells: vec![false; (size.x as usize)*(size.y as usize)],
+        }
+    }
+
+    /// Grid just large enough to hold the given solid cells
+    #[must_use]
+    pub fn from_cells(cells: impl IntoIterator<Item = IVec2>) -> Self {
+        let cells = cells.into_iter().collect::<Vec<_>>();
+        let Some(&first) = cells.first() else { return Self::default(); };
+
+        let (min, max) = cells.iter().fold((first, first), |(min, max), &v| (min.min(v), max.max(v)));
+        let mut result = Self::new(min, (max - min + IVec2::ONE).as_uvec2());
+        cells.into_iter().for_each(|v| result.set(v, true));
+        result
+    }
+
+    #[must_use]
+    pub const fn min(&self) -> IVec2 {
+        self.min
+    }
+
+    #[must_use]
+    pub const fn size(&self) -> UVec2 {
+        self.size
+    }
+
+    /// Cells outside the grid are never solid
+    #[must_use]
+    pub fn get(&self, cell: IVec2) -> bool {
+        self.index(cell).is_some_and(|i| self.cells[i])
+    }
+
+    /// Cells outside the grid are ignored
+    pub fn set(&mut self, cell: IVec2, solid: bool) {
+        if let Some(i) = self.index(cell) {
+            self.cells[i] = solid;
+        }
+    }
+
+    /// Number of solid cells, ie. the entries needed without baking
+    #[must_use]
+    pub fn count(&self) -> usize {
+        self.cells.iter().filter(|&&v| v).count()
+    }
+
+    /// Merges every solid cell into as few rectangles as the greedy pass finds
+    #[must_use]
+    pub fn bake(&self) -> Vec<CollisionBakedRect> {
+        self.bake_region(self.min, self.min + self.size.as_ivec2() - IVec2::ONE)
+    }
+
+    /// As [`CollisionGrid::bake`], but rectangles never cross the edge of a chunk. Chunks are
+    /// aligned to cell zero, so a single chunk can be re-baked with [`CollisionGrid::bake_region`].
+    #[must_use]
+    pub fn bake_chunked(&self, chunk_size: UVec2) -> Vec<CollisionBakedRect> {
+        let chunk_size = chunk_size.max(UVec2::ONE).as_ivec2();
+        let chunk_min  = self.min.div_euclid(chunk_size);
+        let chunk_max  = (self.min + self.size.as_ivec2() - IVec2::ONE).div_euclid(chunk_size);
+
+        let mut result = Vec::new();
+        for y in chunk_min.y..=chunk_max.y {
+            for x in chunk_min.x..=chunk_max.x {
+                let min = IVec2::new(x, y)*chunk_size;
+                result.extend(self.bake_region(min, min + chunk_size - IVec2::ONE));
+            }
+        }
+        result
+    }
+
+    /// Greedily merges the solid cells within the inclusive region. Runs are grown along x
+    /// first, then extended along y while every cell beneath the run is solid.
+    #[must_use]
+    pub fn bake_region(&self, min: IVec2, max: IVec2) -> Vec<CollisionBakedRect> {
+        let min = min.max(self.min);
+        let max = max.min(self.min + self.size.as_ivec2() - IVec2::ONE);
+        if max.x < min.x || max.y < min.y {
+            return Vec::new();
+        }
+
+        let size = (max - min + IVec2::ONE).as_uvec2();
+        let mut merged = vec![false; (size.x as usize)*(size.y as usize)];
+        let local = |x: u32, y: u32| (y as usize)*(size.x as usize) + (x as usize);
+        let free  = |merged: &[bool], x: u32, y: u32| !merged[local(x, y)] && self.get(min + UVec2::new(x, y).as_ivec2());
+
+        let mut result = Vec::new();
+        for y in 0..size.y {
+            for x in 0..size.x {
+                if !free(&merged, x, y) {
+                    continue;
+                }
+
+                let mut width = 1;
+                while x + width < size.x && free(&merged, x + width, y) {
+                    width += 1;
+                }
+
+                let mut height = 1;
+                while y + height < size.y && (x..x + width).all(|i| free(&merged, i, y + height)) {
+                    height += 1;
+                }
+
+                for j in y..y + height {
+                    for i in x..x + width {
+                        merged[local(i, j)] = true;
+                    }
+                }
+
+                result.push(CollisionBakedRect{min: min + UVec2::new(x, y).as_ivec2(), size: UVec2::new(width, height)});
+            }
+        }
+        result
+    }
+
+    fn index(&self, cell: IVec2) -> Option<usize> {
+        let local = cell - self.min;
+        (local.cmpge(IVec2::ZERO).all() && local.as_uvec2().cmplt(self.size).all())
+            .then(|| (local.y as usize)*(self.size.x as usize) + (local.x as usize))
+    }
+
+}
+
+/// A run of solid cells, in cell coordinates
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CollisionBakedRect {
+    pub min:  IVec2,
+    pub size: UVec2,
+}
+
+impl CollisionBakedRect {
+
+    /// Cell count covered by the rectangle
+    #[must_use]
+    pub const fn area(&self) -> u32 {
+        self.size.x*self.size.y
+    }
+
+    /// World-space center, for a grid whose cell zero starts at `origin`
+    #[must_use]
+    pub fn center(&self, origin: Vec2, cell_size: Vec2) -> Vec2 {
+        origin + (self.min.as_vec2() + self.size.as_vec2()/2.0)*cell_size
+    }
+
+    #[must_use]
+    pub fn collider(&self, cell_size: Vec2) -> Rectangle {
+        Rectangle::from_size(self.size.as_vec2()*cell_size)
+    }
+
+}
+
+/// Entries needed for the solid cells before and after baking
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct CollisionBakeReport {
+    pub cells: usize,
+    pub rects: usize,
+}
+
+impl CollisionBakeReport {
+
+    #[must_use]
+    pub fn from_rects(rects: &[CollisionBakedRect]) -> Self {
+        Self {
+            cells: rects.iter().map(|v| v.area() as usize).sum(),
+            rects: rects.len(),
+        }
+    }
+
+}
+
+impl Display for CollisionBakeReport {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let ratio = if self.cells > 0 { 100.0*(self.rects as f32)/(self.cells as f32) } else { 100.0 };
+        write!(f, "{} entries baked to {} ({ratio:.1}%)", self.cells, self.rects)
+    }
+}
+
+impl CollisionMap {
+
+    /// Inserts baked rectangles as static entries, for a grid whose cell zero starts at `origin`.
+    /// Returns the identifiers in the same order, so a re-baked chunk can replace its old entries.
+    pub fn insert_baked(&mut self, rects: &[CollisionBakedRect], origin: Vec2, cell_size: Vec2, filter: CollisionFilter) -> Vec<CollisionMapID> {
+        rects.iter()
+            .map(|v| self.insert(v.center(origin, cell_size), v.collider(cell_size), filter, None))
+            .collect()
+    }
+
+}
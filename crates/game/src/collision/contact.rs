@@ -0,0 +1,21 @@
+// Copyright 2025 Natalie Baker // AGPLv3 //
+
+use bevy::prelude::{Entity, Event};
+
+use super::CollisionHit;
+
+/// Sent for every hit that stopped or deflected a pawn's movement
+#[derive(Debug, Clone, Copy, Event)]
+pub struct ContactEvent {
+    pub pawn:    Entity,
+    pub contact: CollisionHit,
+}
+
+impl ContactEvent {
+
+    #[must_use]
+    pub const fn other(&self) -> Option<Entity> {
+        self.contact.entry.entity
+    }
+
+}
@@ -0,0 +1,344 @@
+// Copyright 2025 Natalie Baker // AGPLv3 //
+
+use core::ops::{BitAnd, BitOr, Not};
+use std::path::Path;
+
+use bevy::prelude::{Component, Resource};
+use bevy_asset_ldtk::schema::EnumDefinition;
+use thiserror::Error;
+
+/// Bitset with one bit per collision layer, bit `n` being layer `n`.
+///
+/// ```
+/// use game::collision::CollisionLayerMask;
+///
+/// let mask = CollisionLayerMask(0b0000_0000_0001_0110);
+/// assert_eq!(mask.layers().collect::<Vec<_>>(), [1, 2, 4]);
+///
+/// assert!(!mask.contains_layer(0));
+/// assert!( mask.contains_layer(1));
+/// assert!( mask.contains_layer(2));
+/// assert!(!mask.contains_layer(3));
+/// assert!( mask.contains_layer(4));
+///
+/// assert_eq!(CollisionLayerMask::NONE.layers().count(), 0);
+/// assert_eq!(CollisionLayerMask::ALL.layers().count(), CollisionLayerMask::LAYER_COUNT);
+/// ```
+#[derive(Debug, Default, Clone, Copy, Hash, PartialEq, Eq)]
+pub struct CollisionLayerMask(pub u32);
+
+impl CollisionLayerMask {
+
+    pub const LAYER_COUNT: usize = u32::BITS as usize;
+
+    pub const NONE: Self = Self(0);
+    pub const ALL:  Self = Self(u32::MAX);
+
+    /// Mask selecting only the given layer.
+    ///
+    /// ```
+    /// use game::collision::CollisionLayerMask;
+    ///
+    /// assert_eq!(CollisionLayerMask::layer(0),  CollisionLayerMask(0b0001));
+    /// assert_eq!(CollisionLayerMask::layer(3),  CollisionLayerMask(0b1000));
+    /// assert_eq!(CollisionLayerMask::layer(31), CollisionLayerMask(0x8000_0000));
+    /// assert_eq!(CollisionLayerMask::layer(3).layers().collect::<Vec<_>>(), [3]);
+    /// ```
+    #[must_use]
+    pub const fn layer(index: usize) -> Self {
+        assert!(index < Self::LAYER_COUNT, "Collision layer index out of range");
+        Self(1 << index)
+    }
+
+    /// Mask selecting every given layer.
+    ///
+    /// ```
+    /// use game::collision::CollisionLayerMask;
+    ///
+    /// let mask = CollisionLayerMask::from_layers([0, 2, 5]);
+    /// assert_eq!(mask, CollisionLayerMask(0b10_0101));
+    /// assert_eq!(mask.layers().collect::<Vec<_>>(), [0, 2, 5]);
+    /// ```
+    #[must_use]
+    pub fn from_layers(layers: impl IntoIterator<Item = usize>) -> Self {
+        layers.into_iter().fold(Self::NONE, |mask, i| mask.with_layer(i))
+    }
+
+    #[must_use]
+    pub const fn with_layer(self, index: usize) -> Self {
+        Self(self.0 | Self::layer(index).0)
+    }
+
+    #[must_use]
+    pub const fn without_layer(self, index: usize) -> Self {
+        Self(self.0 & !Self::layer(index).0)
+    }
+
+    #[must_use]
+    pub const fn contains_layer(self, index: usize) -> bool {
+        index < Self::LAYER_COUNT && (self.0 & (1 << index)) != 0
+    }
+
+    /// True if any layer is set in both masks.
+    ///
+    /// ```
+    /// use game::collision::CollisionLayerMask;
+    ///
+    /// let walls  = CollisionLayerMask::layer(0);
+    /// let pawns  = CollisionLayerMask::layer(1);
+    /// let solids = walls | pawns;
+    ///
+    /// assert!( solids.intersects(walls));
+    /// assert!( solids.intersects(pawns));
+    /// assert!(!walls.intersects(pawns));
+    /// assert!(!CollisionLayerMask::NONE.intersects(CollisionLayerMask::ALL));
+    /// ```
+    #[must_use]
+    pub const fn intersects(self, other: Self) -> bool {
+        (self.0 & other.0) != 0
+    }
+
+    #[must_use]
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Indices of the selected layers, in ascending order
+    pub fn layers(self) -> impl Iterator<Item = usize> {
+        (0..Self::LAYER_COUNT).filter(move |&i| self.contains_layer(i))
+    }
+
+}
+
+impl BitOr for CollisionLayerMask {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl BitAnd for CollisionLayerMask {
+    type Output = Self;
+
+    fn bitand(self, rhs: Self) -> Self::Output {
+        Self(self.0 & rhs.0)
+    }
+}
+
+impl Not for CollisionLayerMask {
+    type Output = Self;
+
+    fn not(self) -> Self::Output {
+        Self(!self.0)
+    }
+}
+
+/// The layers a collider belongs to, and the layers it collides with.
+///
+/// Two filters only interact when each one's mask selects a layer the other is a member of.
+///
+/// ```
+/// use game::collision::{CollisionFilter, CollisionLayerMask};
+///
+/// let walls   = CollisionLayerMask::layer(0);
+/// let pawns   = CollisionLayerMask::layer(1);
+/// let ghosts  = CollisionLayerMask::layer(2);
+///
+/// let wall   = CollisionFilter::new(walls,  CollisionLayerMask::ALL);
+/// let player = CollisionFilter::new(pawns,  walls | pawns);
+/// let ghost  = CollisionFilter::new(ghosts, walls);
+///
+/// assert!( player.interacts_with(wall));
+/// assert!( player.interacts_with(player));
+/// assert!( ghost.interacts_with(wall));
+///
+/// // The player's mask doesn't select ghosts, nor the ghost's mask pawns
+/// assert!(!player.interacts_with(ghost));
+/// assert!(!ghost.interacts_with(player));
+///
+/// // Overriding the player's filter for a single query
+/// let player_ignoring_walls = player.with_mask(pawns);
+/// assert!(!player_ignoring_walls.interacts_with(wall));
+/// ```
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Component)]
+pub struct CollisionFilter {
+    pub memberships: CollisionLayerMask,
+    pub mask:        CollisionLayerMask,
+}
+
+impl CollisionFilter {
+
+    /// Member of layer 0, colliding with everything
+    pub const DEFAULT: Self = Self::new(CollisionLayerMask::layer(0), CollisionLayerMask::ALL);
+
+    /// Member of, and colliding with, everything
+    pub const ALL: Self = Self::new(CollisionLayerMask::ALL, CollisionLayerMask::ALL);
+
+    #[must_use]
+    pub const fn new(memberships: CollisionLayerMask, mask: CollisionLayerMask) -> Self {
+        Self { memberships, mask }
+    }
+
+    #[must_use]
+    pub const fn with_memberships(mut self, memberships: CollisionLayerMask) -> Self {
+        self.memberships = memberships;
+        self
+    }
+
+    #[must_use]
+    pub const fn with_mask(mut self, mask: CollisionLayerMask) -> Self {
+        self.mask = mask;
+        self
+    }
+
+    #[must_use]
+    pub const fn interacts_with(self, other: Self) -> bool {
+        self.mask.intersects(other.memberships) && other.mask.intersects(self.memberships)
+    }
+
+}
+
+impl Default for CollisionFilter {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum CollisionLayerNameError {
+    #[error("Out of layers. Cannot register <{0}>, all {} collision layers are named", CollisionLayerMask::LAYER_COUNT)]
+    Exhausted(String),
+
+    #[error("Unknown layer. No collision layer is named <{0}>")]
+    Unknown(String),
+
+    #[error("Could not access collision layer config: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Invalid collision layer config on line {line}: {reason}")]
+    Parse {
+        line:   usize,
+        reason: String,
+    },
+}
+
+/// Names for collision layers, registered in order from layer 0.
+///
+/// ```
+/// use game::collision::{CollisionLayerMask, CollisionLayerNames};
+///
+/// let names = CollisionLayerNames::from_names(["walls", "pawns", "pickups"]).unwrap();
+/// assert_eq!(names.index("pawns"), Some(1));
+/// assert_eq!(names.name(2), Some("pickups"));
+///
+/// let mask = names.mask(["walls", "pickups"]).unwrap();
+/// assert_eq!(mask, CollisionLayerMask(0b101));
+/// assert_eq!(mask.layers().collect::<Vec<_>>(), [0, 2]);
+///
+/// assert!(names.mask(["water"]).is_err());
+/// ```
+#[derive(Debug, Default, Clone, Resource)]
+pub struct CollisionLayerNames {
+    names: Vec<String>,
+}
+
+impl CollisionLayerNames {
+
+    pub fn from_names(names: impl IntoIterator<Item = impl Into<String>>) -> Result<Self, CollisionLayerNameError> {
+        let mut result = Self::default();
+        for name in names {
+            result.register(name)?;
+        }
+        Ok(result)
+    }
+
+    /// Registers the layers named in a config file, see [`CollisionLayerNames::register_config`]
+    pub fn load(&mut self, path: impl AsRef<Path>) -> Result<CollisionLayerMask, CollisionLayerNameError> {
+        self.register_config(&std::fs::read_to_string(path)?)
+    }
+
+    /// Registers one layer per line of the config, in order. Blank lines and those starting with
+    /// `#` are skipped.
+    ///
+    /// ```
+    /// use game::collision::{CollisionLayerMask, CollisionLayerNames};
+    ///
+    /// let mut names = CollisionLayerNames::default();
+    /// let mask = names.register_config("# Solid\nwalls\npawns\n\n# Triggers\npickups\n").unwrap();
+    /// assert_eq!(mask, CollisionLayerMask(0b111));
+    /// assert_eq!(names.index("pickups"), Some(2));
+    ///
+    /// assert!(names.register_config("deep water").is_err());
+    /// ```
+    pub fn register_config(&mut self, config: &str) -> Result<CollisionLayerMask, CollisionLayerNameError> {
+        let mut mask = CollisionLayerMask::NONE;
+        for (i, line) in config.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if line.contains(char::is_whitespace) {
+                return Err(CollisionLayerNameError::Parse{line: i + 1, reason: format!("Layer names can't contain spaces <{line}>")});
+            }
+            mask = mask.with_layer(self.register(line)?);
+        }
+        Ok(mask)
+    }
+
+    /// Registers every value of an LDtk enum as a layer, in declaration order
+    pub fn register_ldtk_enum(&mut self, definition: &EnumDefinition) -> Result<CollisionLayerMask, CollisionLayerNameError> {
+        definition.values.iter().try_fold(CollisionLayerMask::NONE, |mask, value| {
+            self.register(value.id.as_str()).map(|i| mask.with_layer(i))
+        })
+    }
+
+    /// Returns the layer index for the name, registering it with the next free layer if unknown
+    pub fn register(&mut self, name: impl Into<String>) -> Result<usize, CollisionLayerNameError> {
+        let name = name.into();
+        if let Some(index) = self.index(&name) {
+            return Ok(index);
+        }
+
+        if self.names.len() >= CollisionLayerMask::LAYER_COUNT {
+            return Err(CollisionLayerNameError::Exhausted(name));
+        }
+
+        self.names.push(name);
+        Ok(self.names.len() - 1)
+    }
+
+    #[must_use]
+    pub fn index(&self, name: &str) -> Option<usize> {
+        self.names.iter().position(|v| v == name)
+    }
+
+    #[must_use]
+    pub fn name(&self, index: usize) -> Option<&str> {
+        self.names.get(index).map(String::as_str)
+    }
+
+    pub fn layer(&self, name: &str) -> Result<CollisionLayerMask, CollisionLayerNameError> {
+        self.index(name)
+            .map(CollisionLayerMask::layer)
+            .ok_or_else(|| CollisionLayerNameError::Unknown(name.to_owned()))
+    }
+
+    pub fn mask<'a>(&self, names: impl IntoIterator<Item = &'a str>) -> Result<CollisionLayerMask, CollisionLayerNameError> {
+        names.into_iter().try_fold(CollisionLayerMask::NONE, |mask, name| self.layer(name).map(|v| mask | v))
+    }
+
+    pub fn filter<'a>(
+        &self,
+        memberships: impl IntoIterator<Item = &'a str>,
+        mask: impl IntoIterator<Item = &'a str>,
+    ) -> Result<CollisionFilter, CollisionLayerNameError> {
+        Ok(CollisionFilter::new(self.mask(memberships)?, self.mask(mask)?))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (usize, &str)> {
+        self.names.iter().map(String::as_str).enumerate()
+    }
+
+}
@@ -1,9 +1,9 @@
 // Copyright 2025 Natalie Baker // AGPLv3 //
 
 #[derive(Debug, Default, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
-pub struct CollisionMapLayerID(u64);
+pub struct CollisionMapID(u64);
 
-impl CollisionMapLayerID {
+impl CollisionMapID {
 
     pub const DEFAULT: Self = Self(0);
 
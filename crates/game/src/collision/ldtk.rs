@@ -0,0 +1,156 @@
+// Copyright 2025 Natalie Baker // AGPLv3 //
+
+use bevy::{math::Vec2, prelude::{Entity, Rectangle}};
+use bevy_asset_ldtk::accessors::LdtkEntity;
+use raydee::prelude::{BoxOriented, ShapeStatic};
+use thiserror::Error;
+
+use super::{CollisionFilter, CollisionLayerNameError, CollisionLayerNames, CollisionMap, CollisionMapID};
+
+#[derive(Debug, Error)]
+pub enum LdtkColliderError {
+    #[error("Invalid collision layers on entity <{identifier}>: {source}")]
+    Layers {
+        identifier: String,
+        source: CollisionLayerNameError,
+    },
+
+    #[error("Too few points on entity <{0}>, a polygon needs at least two")]
+    TooFewPoints(String),
+}
+
+/// Turns LDtk entity instances into colliders, so designers can draw collision without tilesets.
+///
+/// Entities are rectangles sized and placed by their width, height and pivot, unless they have
+/// points in their points field. Two points make a single edge, more a closed polygon, built
+/// from thin boxes along each edge so concave shapes work too. Memberships are taken from the
+/// enum values of the layers field, falling back to the builder's filter when it's empty.
+#[derive(Debug, Clone)]
+pub struct LdtkColliderBuilder {
+    identifiers:    Vec<String>,
+    field_points:   String,
+    field_layers:   String,
+    filter:         CollisionFilter,
+    edge_thickness: f32,
+    px_per_unit:    f32,
+}
+
+impl Default for LdtkColliderBuilder {
+    fn default() -> Self {
+        Self {
+            identifiers:    vec!["collider".to_owned()],
+            field_points:   "points".to_owned(),
+            field_layers:   "collision_layer".to_owned(),
+            filter:         CollisionFilter::DEFAULT,
+            edge_thickness: 0.1,
+            px_per_unit:    1.0,
+        }
+    }
+}
+
+impl LdtkColliderBuilder {
+
+    #[must_use]
+    pub fn new(px_per_unit: f32) -> Self {
+        Self { px_per_unit, ..Self::default() }
+    }
+
+    /// Entity identifiers to build colliders from, replacing the default of "collider"
+    #[must_use]
+    pub fn with_identifiers(mut self, identifiers: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.identifiers = identifiers.into_iter().map(Into::into).collect();
+        self
+    }
+
+    #[must_use]
+    pub fn with_points_field(mut self, identifier: impl Into<String>) -> Self {
+        self.field_points = identifier.into();
+        self
+    }
+
+    #[must_use]
+    pub fn with_layers_field(mut self, identifier: impl Into<String>) -> Self {
+        self.field_layers = identifier.into();
+        self
+    }
+
+    #[must_use]
+    pub const fn with_filter(mut self, filter: CollisionFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// Width of the boxes making up polygon edges, in world units
+    #[must_use]
+    pub const fn with_edge_thickness(mut self, thickness: f32) -> Self {
+        self.edge_thickness = thickness;
+        self
+    }
+
+    #[must_use]
+    pub fn matches(&self, entity: &LdtkEntity) -> bool {
+        self.identifiers.iter().any(|v| v == entity.identifier())
+    }
+
+    /// The entity's shapes, placed in world space, along with its filter. Empty when the
+    /// entity's identifier isn't one of the builder's.
+    pub fn build(&self, entity: &LdtkEntity, names: &CollisionLayerNames) -> Result<(Vec<(Vec2, ShapeStatic)>, CollisionFilter), LdtkColliderError> {
+        if !self.matches(entity) {
+            return Ok((Vec::new(), self.filter));
+        }
+
+        let filter = self.build_filter(entity, names)?;
+        let points = entity.field_points_px(&self.field_points);
+        if points.is_empty() {
+            return Ok((vec![self.build_rectangle(entity)], filter));
+        }
+
+        if points.len() < 2 {
+            return Err(LdtkColliderError::TooFewPoints(entity.identifier().clone()));
+        }
+
+        let points = points.into_iter().map(|v| v.as_vec2()/self.px_per_unit).collect::<Vec<_>>();
+        let edges  = if points.len() == 2 { 1 } else { points.len() };
+        let shapes = (0..edges)
+            .filter_map(|i| self.build_edge(points[i], points[(i + 1) % points.len()]))
+            .collect();
+        Ok((shapes, filter))
+    }
+
+    /// Inserts the entity's shapes as static entries, see [`LdtkColliderBuilder::build`]
+    pub fn insert(&self, colliders: &mut CollisionMap, entity: &LdtkEntity, names: &CollisionLayerNames, owner: Option<Entity>) -> Result<Vec<CollisionMapID>, LdtkColliderError> {
+        let (shapes, filter) = self.build(entity, names)?;
+        Ok(shapes.into_iter().map(|(origin, shape)| colliders.insert(origin, shape, filter, owner)).collect())
+    }
+
+}
+
+impl LdtkColliderBuilder {
+
+    fn build_filter(&self, entity: &LdtkEntity, names: &CollisionLayerNames) -> Result<CollisionFilter, LdtkColliderError> {
+        let memberships = names.mask(entity.field_enums(&self.field_layers)).map_err(|source| LdtkColliderError::Layers{
+            identifier: entity.identifier().clone(),
+            source,
+        })?;
+
+        Ok(if memberships.is_empty() { self.filter } else { self.filter.with_memberships(memberships) })
+    }
+
+    fn build_rectangle(&self, entity: &LdtkEntity) -> (Vec2, ShapeStatic) {
+        // Pivots are measured downwards from the top-left, but the pivot position has y flipped upwards
+        let size   = entity.size_px().as_vec2();
+        let pivot  = entity.pivot();
+        let center = entity.pivot_px().as_vec2() + Vec2::new(0.5 - pivot.x, pivot.y - 0.5)*size;
+        (center/self.px_per_unit, Rectangle::from_size(size/self.px_per_unit).into())
+    }
+
+    fn build_edge(&self, start: Vec2, end: Vec2) -> Option<(Vec2, ShapeStatic)> {
+        let offset = end - start;
+        let length = offset.length();
+        (length > 0.0).then(|| {
+            let half_size = Vec2::new(length, self.edge_thickness)/2.0;
+            ((start + end)/2.0, BoxOriented::new(half_size, offset/length).into())
+        })
+    }
+
+}
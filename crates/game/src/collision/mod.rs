@@ -1,56 +1,277 @@
 // Copyright 2025 Natalie Baker // AGPLv3 //
 
-use bevy::prelude::{Vec2, Entity, Resource};
+use bevy::{math::IVec2, prelude::{Vec2, Entity, Resource}, platform_support::collections::hash_map::HashMap};
+
+mod bake;
+pub use bake::*;
+
+mod contact;
+pub use contact::*;
 
 mod identifiers;
 pub use identifiers::*;
 
-mod layer;
-pub use layer::*;
+mod ldtk;
+pub use ldtk::*;
+
+mod filter;
+pub use filter::*;
+
+mod motion_context;
+pub use motion_context::*;
+
+mod plugin;
+pub use plugin::*;
+
+mod query;
+pub use query::*;
+
+mod sensor;
+pub use sensor::*;
+
+mod tile_shapes;
+pub use tile_shapes::*;
 
-use raydee::prelude::ShapeStatic;
+mod visibility;
+pub use visibility::*;
+
+use raydee::prelude::{ShapeCommon, ShapeStatic};
 
 #[derive(Debug, Clone, Copy)]
 pub struct CollisionMapEntry {
-    pub identifier: CollisionMapLayerID,
+    pub identifier: CollisionMapID,
     pub entity:     Option<Entity>,
     pub collider:   ShapeStatic,
     pub origin:     Vec2,
+    pub filter:     CollisionFilter,
+    /// Sensors report overlaps but never block motion
+    pub sensor:     bool,
+    /// Dynamic entries, ie. pawns, move every frame and aren't part of [`CollisionMap::revision`]
+    pub dynamic:    bool,
 }
 
 #[derive(Debug, Default, Resource)]
 pub struct CollisionMap {
-    layers: [CollisionMapLayer; 16],
+    identifier_next: CollisionMapID,
+    revision: u64,
+    revision_dynamic: u64,
+    generation: u64,
+    ranges: HashMap<CollisionMapID, ([IVec2; 2], CollisionMapEntry)>,
+    entries: HashMap<IVec2, Vec<CollisionMapEntry>>,
+    entities: HashMap<Entity, Vec<CollisionMapID>>,
+    chunk_bounds: Option<[IVec2; 2]>,
 }
 
 impl CollisionMap {
 
+    const CHUNK_SHR: u32 = 3;
+    pub const CHUNK_SIZE: f32 = (1 << Self::CHUNK_SHR) as f32;
+
     pub fn clear(&mut self) {
-        self.layers.iter_mut().for_each(CollisionMapLayer::clear);
+        self.revision += 1;
+        self.revision_dynamic += 1;
+        self.generation += 1;
+        self.ranges.clear();
+        self.entries.clear();
+        self.entities.clear();
+        self.chunk_bounds = None;
     }
 
-    pub fn iter(&self) -> impl Iterator<Item = &CollisionMapLayer> {
-        self.layers.iter()
+    pub fn iter(&self) -> impl Iterator<Item = &CollisionMapEntry> {
+        self.ranges.values().map(|v| &v.1)
     }
 
+    /// Changes whenever a non-dynamic entry is inserted or removed
     #[must_use]
-    pub fn get(&self, layer: usize) -> &CollisionMapLayer {
-        self.try_get(layer).unwrap()
+    pub const fn revision(&self) -> u64 {
+        self.revision
     }
 
+    /// Changes whenever the map is cleared
     #[must_use]
-    pub fn get_mut(&mut self, layer: usize) -> &mut CollisionMapLayer {
-        self.try_get_mut(layer).unwrap()
+    pub const fn generation(&self) -> u64 {
+        self.generation
     }
 
+    /// Changes whenever a dynamic entry is inserted or removed
     #[must_use]
-    pub fn try_get(&self, layer: usize) -> Option<&CollisionMapLayer> {
-        (layer < self.layers.len()).then(|| &self.layers[layer])
+    pub const fn revision_dynamic(&self) -> u64 {
+        self.revision_dynamic
+    }
+
+    #[must_use]
+    pub fn get(&self, identifier: CollisionMapID) -> Option<&CollisionMapEntry> {
+        self.ranges.get(&identifier).map(|v| &v.1)
+    }
+
+    /// Chunks that have held entries since the last clear, conservative as it never shrinks on removal
+    #[must_use]
+    pub const fn chunk_bounds(&self) -> Option<[IVec2; 2]> {
+        self.chunk_bounds
+    }
+
+    #[must_use]
+    pub fn chunk_entries(&self, chunk: IVec2) -> &[CollisionMapEntry] {
+        self.entries.get(&chunk).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    #[must_use]
+    pub fn entity_entries(&self, entity: Entity) -> &[CollisionMapID] {
+        self.entities.get(&entity).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    /// Visits every chunk touched by the bounds, entries spanning several chunks are visited once per chunk
+    pub fn visit(&self, start: Vec2, end: Vec2, mut process: impl FnMut(&[CollisionMapEntry])) {
+        let bounds = [
+            Self::calculate_chunk(start),
+            Self::calculate_chunk(end  ),
+        ];
+
+        for x in bounds[0].x..=bounds[1].x {
+            for y in bounds[0].y..=bounds[1].y {
+                if let Some(entries) = self.entries.get(&IVec2::new(x, y))  {
+                    process(entries);
+                }
+            }
+        }
+    }
+
+    /// As [`CollisionMap::visit`], but only entries the filter interacts with
+    pub fn visit_filtered(&self, start: Vec2, end: Vec2, filter: CollisionFilter, mut process: impl FnMut(&CollisionMapEntry)) {
+        self.visit(start, end, |v| v.iter().filter(|e| filter.interacts_with(e.filter)).for_each(&mut process));
+    }
+
+    pub fn insert(&mut self, origin: Vec2, collider: impl Into<ShapeStatic>, filter: CollisionFilter, entity: Option<Entity>) -> CollisionMapID {
+        self.insert_entry(origin, collider.into(), filter, entity, false, false)
+    }
+
+    pub fn insert_sensor(&mut self, origin: Vec2, collider: impl Into<ShapeStatic>, filter: CollisionFilter, entity: Option<Entity>) -> CollisionMapID {
+        self.insert_entry(origin, collider.into(), filter, entity, true, false)
+    }
+
+    pub fn insert_dynamic(&mut self, origin: Vec2, collider: impl Into<ShapeStatic>, filter: CollisionFilter, entity: Option<Entity>) -> CollisionMapID {
+        self.insert_entry(origin, collider.into(), filter, entity, false, true)
+    }
+
+    fn insert_entry(&mut self, origin: Vec2, collider: ShapeStatic, filter: CollisionFilter, entity: Option<Entity>, sensor: bool, dynamic: bool) -> CollisionMapID {
+        let identifier = self.identifier_next;
+        self.identifier_next = identifier.next().expect("Exhausted Collision Map IDs");
+        self.bump_revision(dynamic);
+
+        let entry = CollisionMapEntry{
+            identifier,
+            entity,
+            collider,
+            origin,
+            filter,
+            sensor,
+            dynamic,
+        };
+
+        let bounds = Self::calculate_bounds(origin, collider.bounding_box());
+        self.ranges.insert(identifier, (bounds, entry));
+
+        if let Some(entity) = entity {
+            self.entities.entry(entity).or_default().push(identifier);
+        }
+
+        self.chunk_bounds = Some(match self.chunk_bounds {
+            Some([min, max]) => [min.min(bounds[0]), max.max(bounds[1])],
+            None => bounds,
+        });
+
+        for x in bounds[0].x..=bounds[1].x {
+            for y in bounds[0].y..=bounds[1].y {
+                let idx = IVec2::new(x, y);
+                self.entries.entry(idx).or_default().push(entry);
+            }
+        }
+
+        identifier
+    }
+
+    pub fn remove(&mut self, identifier: CollisionMapID) -> bool {
+        if let Some((bounds, removed)) = self.ranges.remove(&identifier) {
+            self.bump_revision(removed.dynamic);
+
+            if let Some(entity) = removed.entity {
+                if let Some(identifiers) = self.entities.get_mut(&entity) {
+                    identifiers.retain(|v| *v != identifier);
+                    if identifiers.is_empty() {
+                        self.entities.remove(&entity);
+                    }
+                }
+            }
+
+            for x in bounds[0].x..=bounds[1].x {
+                for y in bounds[0].y..=bounds[1].y {
+                    let idx = IVec2::new(x, y);
+                    let entry = self.entries.get_mut(&idx).unwrap();
+                    let idx = entry.iter().enumerate().find_map(|(i, v)| (v.identifier == identifier).then_some(i)).unwrap();
+                    entry.swap_remove(idx);
+                }
+            }
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn remove_entity(&mut self, entity: Entity) -> bool {
+        if let Some(identifiers) = self.entities.remove(&entity) {
+            for identifier in identifiers {
+                self.remove(identifier);
+            }
+            true
+        } else {
+            false
+        }
+    }
+ 
+    const fn bump_revision(&mut self, dynamic: bool) {
+        if dynamic {
+            self.revision_dynamic += 1;
+        } else {
+            self.revision += 1;
+        }
     }
 
     #[must_use]
-    pub fn try_get_mut(&mut self, layer: usize) -> Option<&mut CollisionMapLayer> {
-        (layer < self.layers.len()).then(|| &mut self.layers[layer])
+    pub fn calculate_chunk(point: Vec2) ->IVec2 {
+        // Floor rather than truncate, otherwise the chunks either side of zero overlap
+        point.floor().as_ivec2().map(|v| v >> Self::CHUNK_SHR)
+    }
+ 
+    #[must_use]
+    pub fn calculate_bounds(origin: Vec2, bounds: [Vec2; 2]) -> [IVec2; 2] {
+        [
+            Self::calculate_chunk(origin + bounds[0]),
+            Self::calculate_chunk(origin + bounds[1]),
+        ]
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::math::{IVec2, Vec2};
+
+    use super::CollisionMap;
+
+    #[test]
+    fn chunks_are_the_same_size_either_side_of_zero() {
+        assert_eq!(CollisionMap::calculate_chunk(Vec2::new( 0.5,  7.9)), IVec2::new( 0,  0));
+        assert_eq!(CollisionMap::calculate_chunk(Vec2::new(-0.5, -0.1)), IVec2::new(-1, -1));
+        assert_eq!(CollisionMap::calculate_chunk(Vec2::new(-8.0, -8.1)), IVec2::new(-1, -2));
     }
 
-}
\ No newline at end of file
+    #[test]
+    fn chunk_origin_is_its_lower_corner() {
+        // Ray traversal steps between chunks at multiples of the chunk size
+        for point in [Vec2::new(-0.5, 3.0), Vec2::new(-12.25, -7.5), Vec2::new(9.0, -16.0)] {
+            let chunk = CollisionMap::calculate_chunk(point);
+            let min   = chunk.as_vec2()*CollisionMap::CHUNK_SIZE;
+            assert!(point.cmpge(min).all() && point.cmplt(min + CollisionMap::CHUNK_SIZE).all(), "{point} in {chunk}");
+        }
+    }
+}
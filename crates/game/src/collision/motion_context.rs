@@ -0,0 +1,189 @@
+// Copyright 2025 Natalie Baker // AGPLv3 //
+
+use bevy::{math::Vec2, platform_support::collections::hash_set::HashSet, prelude::Entity};
+use raydee::prelude::*;
+
+use super::{CollisionFilter, CollisionHit, CollisionMap, CollisionMapEntry};
+
+#[derive(Debug, Clone, Copy)]
+pub struct MotionQueryCandidate {
+    pub entry:    CollisionMapEntry,
+    pub combined: ShapeCombined,
+    pub bounds:   [Vec2; 2],
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct MotionQueryContextKey {
+    collider: ShapeMoving,
+    skin_distance: f32,
+    filter: CollisionFilter,
+    revision: u64,
+}
+
+/// Gathers [`CollisionMap`] candidates once for a motion's swept bounds, and caches the combined
+/// shapes so they can be reused across slide iterations. Keeping the context around between
+/// frames lets slow-moving pawns skip gathering entirely while they stay within the margin.
+/// Dynamic entries move too often to cache, and are gathered again for every motion.
+#[derive(Debug)]
+pub struct MotionQueryContext {
+    key:        Option<MotionQueryContextKey>,
+    margin:     f32,
+    bounds:     [Vec2; 2],
+    candidates: Vec<MotionQueryCandidate>,
+    sensors:    Vec<MotionQueryCandidate>,
+    dynamic:    Vec<MotionQueryCandidate>,
+    path:       Vec<Vec2>,
+    contacts:   Vec<CollisionHit>,
+}
+
+impl MotionQueryContext {
+
+    pub const DEFAULT_MARGIN: f32 = 0.5;
+
+    #[must_use]
+    pub const fn new(margin: f32) -> Self {
+        Self {
+            key: None,
+            margin,
+            bounds: [Vec2::ZERO, Vec2::ZERO],
+            candidates: Vec::new(),
+            sensors: Vec::new(),
+            dynamic: Vec::new(),
+            path: Vec::new(),
+            contacts: Vec::new(),
+        }
+    }
+
+    pub fn invalidate(&mut self) {
+        self.key = None;
+    }
+
+    /// Returns true when the cached candidates could be reused
+    pub fn prepare(
+        &mut self,
+        colliders: &CollisionMap,
+        filter: CollisionFilter,
+        collider: ShapeMoving,
+        skin_distance: f32,
+        bounds: [Vec2; 2],
+    ) -> bool {
+        let key = MotionQueryContextKey{
+            collider,
+            skin_distance,
+            filter,
+            revision: colliders.revision(),
+        };
+
+        if self.key == Some(key) && self.bounds[0].cmple(bounds[0]).all() && self.bounds[1].cmpge(bounds[1]).all() {
+            return true;
+        }
+
+        self.key    = Some(key);
+        self.bounds = [bounds[0] - self.margin, bounds[1] + self.margin];
+        self.candidates.clear();
+        self.sensors.clear();
+
+        let [min, max]  = self.bounds;
+        let candidates  = &mut self.candidates;
+        let sensors     = &mut self.sensors;
+        let mut visited = HashSet::default();
+        colliders.visit_filtered(min, max, filter, |entry| {
+            // Entries are stored in every chunk they touch
+            if !entry.dynamic && visited.insert(entry.identifier) {
+                let combined = MotionQuery::combine(collider, skin_distance, &entry.collider);
+                let bounds   = combined.bounding_box();
+                let target   = if entry.sensor { &mut *sensors } else { &mut *candidates };
+                target.push(MotionQueryCandidate{
+                    entry: *entry,
+                    combined,
+                    bounds: [entry.origin + bounds[0], entry.origin + bounds[1]],
+                });
+            }
+        });
+
+        false
+    }
+
+    /// Gathers the dynamic candidates within the bounds, ignoring those belonging to the given entity
+    pub fn prepare_dynamic(
+        &mut self,
+        colliders: &CollisionMap,
+        filter: CollisionFilter,
+        collider: ShapeMoving,
+        skin_distance: f32,
+        bounds: [Vec2; 2],
+        ignore: Option<Entity>,
+    ) {
+        self.dynamic.clear();
+
+        let dynamic = &mut self.dynamic;
+        let mut visited = HashSet::default();
+        colliders.visit_filtered(bounds[0], bounds[1], filter, |entry| {
+            if entry.dynamic && !entry.sensor && (ignore.is_none() || entry.entity != ignore) && visited.insert(entry.identifier) {
+                let combined = match ShapeMoving::try_from(entry.collider) {
+                    Ok(other) => MotionQuery::combine_moving(collider, skin_distance, &other),
+                    Err(other) => MotionQuery::combine(collider, skin_distance, &other),
+                };
+                let bounds = combined.bounding_box();
+                dynamic.push(MotionQueryCandidate{
+                    entry: *entry,
+                    combined,
+                    bounds: [entry.origin + bounds[0], entry.origin + bounds[1]],
+                });
+            }
+        });
+    }
+
+    /// Candidates whose combined shape could be hit by an origin travelling within the bounds
+    pub fn visit(&self, min: Vec2, max: Vec2) -> impl Iterator<Item = &MotionQueryCandidate> {
+        self.candidates.iter()
+            .chain(self.dynamic.iter())
+            .filter(move |c| c.bounds[0].cmple(max).all() && c.bounds[1].cmpge(min).all())
+    }
+
+    #[must_use]
+    pub fn candidates(&self) -> &[MotionQueryCandidate] {
+        &self.candidates
+    }
+
+    #[must_use]
+    pub fn dynamic(&self) -> &[MotionQueryCandidate] {
+        &self.dynamic
+    }
+
+    /// Sensors within the prepared bounds, these are never returned by [`MotionQueryContext::visit`]
+    #[must_use]
+    pub fn sensors(&self) -> &[MotionQueryCandidate] {
+        &self.sensors
+    }
+
+    /// Positions the last solved motion passed through, from its origin to where it came to rest
+    #[must_use]
+    pub fn path(&self) -> &[Vec2] {
+        &self.path
+    }
+
+    /// Hits accepted by the last solved motion, in the order they occurred
+    #[must_use]
+    pub fn contacts(&self) -> &[CollisionHit] {
+        &self.contacts
+    }
+
+    pub fn begin_path(&mut self, origin: Vec2) {
+        self.path.clear();
+        self.path.push(origin);
+        self.contacts.clear();
+    }
+
+    pub fn extend_path(&mut self, point: Vec2, contact: Option<CollisionHit>) {
+        self.path.push(point);
+        self.contacts.extend(contact);
+    }
+
+}
+
+impl Default for MotionQueryContext {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_MARGIN)
+    }
+}
@@ -0,0 +1,82 @@
+// Copyright 2025 Natalie Baker // AGPLv3 //
+
+use bevy::{prelude::*, transform::TransformSystem};
+use raydee::prelude::ShapeStatic;
+
+use super::{CollisionFilter, CollisionLayerNames, CollisionMap, ContactEvent, SensorEvent, TileShapeRegistry};
+
+/// Registers the [`CollisionLayerNames`] from the config file, when given and present, ahead of
+/// any registered from LDtk enums
+#[derive(Debug, Default, Clone)]
+pub struct PluginCollision {
+    pub layers: Option<String>,
+}
+
+impl Plugin for PluginCollision {
+    fn build(&self, app: &mut App) {
+        let mut names = CollisionLayerNames::default();
+        if let Some(path) = self.layers.as_ref().filter(|v| std::path::Path::new(v).exists()) {
+            if let Err(e) = names.load(path) {
+                bevy::log::warn!("{e}");
+                names = CollisionLayerNames::default();
+            }
+        }
+
+        app
+            .init_resource::<CollisionMap>()
+            .insert_resource(names)
+            .init_resource::<TileShapeRegistry>()
+            .add_event::<SensorEvent>()
+            .add_event::<ContactEvent>()
+            .add_systems(PostUpdate, collider_sync.after(TransformSystem::TransformPropagate));
+    }
+}
+
+/// Registers the entity in the [`CollisionMap`], filtered by its [`CollisionFilter`] or the default if absent
+#[derive(Debug, Clone, Copy, Component)]
+pub struct Collider(pub ShapeStatic);
+
+/// Registers a [`Collider`] as a sensor, reporting overlaps without blocking
+#[derive(Debug, Default, Clone, Copy, Component)]
+pub struct Sensor;
+
+pub fn collider_sync(
+    mut r_collision_map: ResMut<CollisionMap>,
+    mut l_generation: Local<u64>,
+
+    q_colliders: Query<(&Collider, &GlobalTransform, Option<&CollisionFilter>, Has<Sensor>)>,
+    q_changed: Query<Entity, (With<Collider>, Or<(Changed<Collider>, Changed<GlobalTransform>, Changed<CollisionFilter>, Added<Sensor>)>)>,
+    q_registered: Query<Entity, With<Collider>>,
+
+    mut ev_removed_colliders: RemovedComponents<Collider>,
+    mut ev_removed_filters: RemovedComponents<CollisionFilter>,
+    mut ev_removed_sensors: RemovedComponents<Sensor>,
+) {
+    // The map was cleared (ie. a level reload), re-register anything it dropped
+    let missing: Vec<Entity> = if *l_generation == r_collision_map.generation() {
+        Vec::new()
+    } else {
+        *l_generation = r_collision_map.generation();
+        q_registered.iter().filter(|&e| r_collision_map.entity_entries(e).is_empty()).collect()
+    };
+
+    for entity in ev_removed_colliders.read() {
+        r_collision_map.remove_entity(entity);
+    }
+
+    // Entries are re-registered rather than moved, as a change can move them between chunks
+    for entity in q_changed.iter().chain(ev_removed_filters.read()).chain(ev_removed_sensors.read()).chain(missing) {
+        let Ok((collider, transform, filter, sensor)) = q_colliders.get(entity) else { continue; };
+        let filter = filter.copied().unwrap_or_default();
+
+        // NOTE Colliders are axis aligned, only translation is taken from the transform
+        let origin = transform.translation().truncate();
+
+        r_collision_map.remove_entity(entity);
+        if sensor {
+            r_collision_map.insert_sensor(origin, collider.0, filter, Some(entity));
+        } else {
+            r_collision_map.insert(origin, collider.0, filter, Some(entity));
+        }
+    }
+}
@@ -0,0 +1,314 @@
+// Copyright 2025 Natalie Baker // AGPLv3 //
+
+use bevy::{math::{IVec2, Vec2}, platform_support::collections::hash_set::HashSet, prelude::{Entity, Rectangle}};
+use raydee::prelude::*;
+
+use super::{CollisionFilter, CollisionMap, CollisionMapEntry, CollisionMapID};
+
+#[derive(Debug, Clone, Copy)]
+pub struct CollisionHit {
+    pub entry: CollisionMapEntry,
+    pub hit:   RayIntersection,
+}
+
+impl CollisionHit {
+
+    #[must_use]
+    pub const fn entity(&self) -> Option<Entity> {
+        self.entry.entity
+    }
+
+    #[must_use]
+    pub const fn distance(&self) -> f32 {
+        self.hit.distance
+    }
+
+    /// How far past the contact a mover heading for `target` wanted to go, along the contact normal
+    #[must_use]
+    pub fn push_towards(&self, target: Vec2) -> Option<Vec2> {
+        let push = (target - self.hit.point).dot(-self.hit.normal);
+        (push > 0.0).then_some(-self.hit.normal*push)
+    }
+
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct CollisionNearest {
+    pub entry:    CollisionMapEntry,
+    pub distance: f32,
+}
+
+impl CollisionNearest {
+
+    #[must_use]
+    pub const fn entity(&self) -> Option<Entity> {
+        self.entry.entity
+    }
+
+}
+
+// NOTE Casts ignore colliders they start inside of, as there's no meaningful entry point or normal
+
+impl CollisionMap {
+
+    /// Closest collider along the ray, direction must be normalized
+    #[must_use]
+    pub fn ray_cast(&self, origin: Vec2, direction: Vec2, max_distance: f32, filter: CollisionFilter) -> Option<CollisionHit> {
+        let caster = RayCaster::new(origin, direction);
+        self.cast_closest(origin, direction, max_distance, IVec2::ZERO, filter, |entry| {
+            entry.collider.raycast_enter(entry.origin, &caster)
+        })
+    }
+
+    /// Every collider along the ray, sorted by distance, direction must be normalized
+    #[must_use]
+    pub fn ray_cast_all(&self, origin: Vec2, direction: Vec2, max_distance: f32, filter: CollisionFilter) -> Vec<CollisionHit> {
+        let caster = RayCaster::new(origin, direction);
+        self.cast_all(origin, direction, max_distance, IVec2::ZERO, filter, |entry| {
+            entry.collider.raycast_enter(entry.origin, &caster)
+        })
+    }
+
+    /// Closest collider the shape would touch moving along the direction, which must be normalized.
+    /// The hit point is the shape's origin at the time of impact, not the contact point.
+    #[must_use]
+    pub fn shape_cast(&self, shape: impl Into<ShapeMoving>, origin: Vec2, direction: Vec2, max_distance: f32, filter: CollisionFilter) -> Option<CollisionHit> {
+        let shape  = shape.into();
+        let caster = RayCaster::new(origin, direction);
+        self.cast_closest(origin, direction, max_distance, Self::calculate_chunk_reach(&shape), filter, |entry| {
+            ShapeCombined::between_moving_and_static(&shape, &entry.collider).raycast_enter(entry.origin, &caster)
+        })
+    }
+
+    /// Every collider the shape would touch moving along the direction, sorted by distance
+    #[must_use]
+    pub fn shape_cast_all(&self, shape: impl Into<ShapeMoving>, origin: Vec2, direction: Vec2, max_distance: f32, filter: CollisionFilter) -> Vec<CollisionHit> {
+        let shape  = shape.into();
+        let caster = RayCaster::new(origin, direction);
+        self.cast_all(origin, direction, max_distance, Self::calculate_chunk_reach(&shape), filter, |entry| {
+            ShapeCombined::between_moving_and_static(&shape, &entry.collider).raycast_enter(entry.origin, &caster)
+        })
+    }
+
+    /// Colliders overlapping the axis-aligned box
+    #[must_use]
+    pub fn overlap_aabb(&self, min: Vec2, max: Vec2, filter: CollisionFilter) -> Vec<CollisionMapEntry> {
+        let center = (min + max)/2.0;
+        let shape  = ShapeMoving::from(Rectangle{half_size: (max - min)/2.0});
+        self.collect_unique(min, max, filter, |entry| {
+            let combined = ShapeCombined::between_moving_and_static(&shape, &entry.collider);
+            combined.contains_point(entry.origin, center)
+        })
+    }
+
+    /// Colliders containing the point
+    #[must_use]
+    pub fn overlap_point(&self, point: Vec2, filter: CollisionFilter) -> Vec<CollisionMapEntry> {
+        self.chunk_entries(Self::calculate_chunk(point)).iter()
+            .filter(|entry| filter.interacts_with(entry.filter))
+            .filter(|entry| entry.collider.contains_point(entry.origin, point))
+            .copied()
+            .collect()
+    }
+
+    /// Up to `count` colliders closest to the point, sorted by distance. Colliders containing the point have a distance of zero.
+    #[must_use]
+    pub fn nearest(&self, point: Vec2, count: usize, max_distance: f32, filter: CollisionFilter) -> Vec<CollisionNearest> {
+        let mut found = Vec::<CollisionNearest>::new();
+        let Some([bounds_min, bounds_max]) = self.chunk_bounds else { return found; };
+        if count == 0 {
+            return found;
+        }
+
+        let center = Self::calculate_chunk(point);
+        let mut visited = HashSet::<CollisionMapID>::default();
+
+        // Walk rings of chunks outwards, anything not yet found is at least a ring's distance away
+        for ring in 0.. {
+            let ring_min = center - IVec2::splat(ring);
+            let ring_max = center + IVec2::splat(ring);
+
+            for_each_ring_chunk(ring_min, ring_max, |chunk| {
+                for entry in self.chunk_entries(chunk) {
+                    if filter.interacts_with(entry.filter) && visited.insert(entry.identifier) {
+                        let distance = entry.collider.distance_to_point(point - entry.origin);
+                        if distance <= max_distance {
+                            found.push(CollisionNearest{entry: *entry, distance});
+                        }
+                    }
+                }
+            });
+
+            found.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+            found.truncate(count);
+
+            let covered = ring_min.cmple(bounds_min).all() && ring_max.cmpge(bounds_max).all();
+            let reached = (ring as f32)*Self::CHUNK_SIZE;
+            if covered || reached > max_distance || (found.len() == count && found[count-1].distance <= reached) {
+                break;
+            }
+        }
+
+        found
+    }
+
+}
+
+impl CollisionMap {
+
+    fn calculate_chunk_reach(shape: &ShapeMoving) -> IVec2 {
+        let [min, max] = shape.bounding_box();
+        (min.abs().max(max.abs())/Self::CHUNK_SIZE).ceil().as_ivec2()
+    }
+
+    pub(super) fn cast_closest(
+        &self,
+        origin: Vec2,
+        direction: Vec2,
+        max_distance: f32,
+        reach: IVec2,
+        filter: CollisionFilter,
+        mut test: impl FnMut(&CollisionMapEntry) -> Option<RayIntersection>,
+    ) -> Option<CollisionHit> {
+        let mut visited = HashSet::<CollisionMapID>::default();
+        let mut closest = None::<CollisionHit>;
+        self.walk_chunks(origin, direction, max_distance, |chunk, t_exit| {
+            for_each_chunk(chunk - reach, chunk + reach, |chunk| {
+                for entry in self.chunk_entries(chunk) {
+                    if !filter.interacts_with(entry.filter) || !visited.insert(entry.identifier) {
+                        continue;
+                    }
+
+                    let Some(hit) = test(entry) else { continue; };
+                    if hit.distance >= 0.0 && hit.distance <= max_distance && closest.is_none_or(|v| hit.distance < v.hit.distance) {
+                        closest = Some(CollisionHit{entry: *entry, hit});
+                    }
+                }
+            });
+
+            // Anything unvisited must be hit beyond this chunk
+            closest.is_none_or(|v| v.hit.distance > t_exit)
+        });
+        closest
+    }
+
+    fn cast_all(
+        &self,
+        origin: Vec2,
+        direction: Vec2,
+        max_distance: f32,
+        reach: IVec2,
+        filter: CollisionFilter,
+        mut test: impl FnMut(&CollisionMapEntry) -> Option<RayIntersection>,
+    ) -> Vec<CollisionHit> {
+        let mut visited = HashSet::<CollisionMapID>::default();
+        let mut result  = Vec::<CollisionHit>::new();
+        self.walk_chunks(origin, direction, max_distance, |chunk, _| {
+            for_each_chunk(chunk - reach, chunk + reach, |chunk| {
+                for entry in self.chunk_entries(chunk) {
+                    if !filter.interacts_with(entry.filter) || !visited.insert(entry.identifier) {
+                        continue;
+                    }
+
+                    if let Some(hit) = test(entry).filter(|v| v.distance >= 0.0 && v.distance <= max_distance) {
+                        result.push(CollisionHit{entry: *entry, hit});
+                    }
+                }
+            });
+            true
+        });
+        result.sort_by(|a, b| a.hit.distance.total_cmp(&b.hit.distance));
+        result
+    }
+
+    pub(super) fn collect_unique(&self, min: Vec2, max: Vec2, filter: CollisionFilter, mut test: impl FnMut(&CollisionMapEntry) -> bool) -> Vec<CollisionMapEntry> {
+        let mut visited = HashSet::<CollisionMapID>::default();
+        let mut result  = Vec::new();
+        self.visit_filtered(min, max, filter, |entry| {
+            if visited.insert(entry.identifier) && test(entry) {
+                result.push(*entry);
+            }
+        });
+        result
+    }
+
+    /// Visits chunks in the order the ray passes through them, along with the distance at which the
+    /// ray leaves the chunk, until the visitor returns false or the ray leaves the populated chunks.
+    pub fn walk_chunks(&self, origin: Vec2, direction: Vec2, max_distance: f32, mut visit: impl FnMut(IVec2, f32) -> bool) {
+        let Some([bounds_min, bounds_max]) = self.chunk_bounds else { return; };
+
+        // Clip the ray to the populated chunks, so unbounded rays terminate
+        let caster = RayCaster::new(origin, direction);
+        let bounds_min  = bounds_min.as_vec2()*Self::CHUNK_SIZE;
+        let bounds_max  = (bounds_max + IVec2::ONE).as_vec2()*Self::CHUNK_SIZE;
+        let Some([enter, exit]) = caster.test_rect((bounds_min + bounds_max)/2.0, (bounds_max - bounds_min)/2.0) else { return; };
+        let t_start = enter.distance.max(0.0);
+        let t_end   = exit.distance.min(max_distance);
+        if t_start > t_end {
+            return;
+        }
+
+        // Amanatides & Woo, stepping from the clipped start
+        let start = origin + direction*t_start;
+        let mut chunk = Self::calculate_chunk(start);
+        let step = IVec2::new(
+            if direction.x > 0.0 { 1 } else if direction.x < 0.0 { -1 } else { 0 },
+            if direction.y > 0.0 { 1 } else if direction.y < 0.0 { -1 } else { 0 },
+        );
+        let t_delta = (Self::CHUNK_SIZE/direction).abs();
+        let mut t_next = Vec2::new(
+            Self::calculate_chunk_boundary(start.x, direction.x, chunk.x),
+            Self::calculate_chunk_boundary(start.y, direction.y, chunk.y),
+        ) + t_start;
+
+        loop {
+            let t_exit = t_next.x.min(t_next.y);
+            if !visit(chunk, t_exit.min(t_end)) || t_exit >= t_end {
+                break;
+            }
+
+            if t_next.x < t_next.y {
+                chunk.x  += step.x;
+                t_next.x += t_delta.x;
+            } else {
+                chunk.y  += step.y;
+                t_next.y += t_delta.y;
+            }
+        }
+    }
+
+    fn calculate_chunk_boundary(start: f32, direction: f32, chunk: i32) -> f32 {
+        if direction > 0.0 {
+            (((chunk + 1) as f32)*Self::CHUNK_SIZE - start)/direction
+        } else if direction < 0.0 {
+            ((chunk as f32)*Self::CHUNK_SIZE - start)/direction
+        } else {
+            f32::INFINITY
+        }
+    }
+
+}
+
+fn for_each_chunk(min: IVec2, max: IVec2, mut process: impl FnMut(IVec2)) {
+    for x in min.x..=max.x {
+        for y in min.y..=max.y {
+            process(IVec2::new(x, y));
+        }
+    }
+}
+
+fn for_each_ring_chunk(min: IVec2, max: IVec2, mut process: impl FnMut(IVec2)) {
+    if min == max {
+        process(min);
+        return;
+    }
+
+    for x in min.x..=max.x {
+        process(IVec2::new(x, min.y));
+        process(IVec2::new(x, max.y));
+    }
+    for y in (min.y + 1)..max.y {
+        process(IVec2::new(min.x, y));
+        process(IVec2::new(max.x, y));
+    }
+}
@@ -0,0 +1,23 @@
+// Copyright 2025 Natalie Baker // AGPLv3 //
+
+use bevy::prelude::{Entity, Event};
+
+use super::CollisionMapID;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SensorTransition {
+    Entered,
+    Stayed,
+    Exited,
+}
+
+/// Sent when a pawn's movement changes, or keeps, its overlap with a sensor. A pawn passing
+/// entirely through a sensor in a single move is sent both [`SensorTransition::Entered`] and
+/// [`SensorTransition::Exited`].
+#[derive(Debug, Clone, Copy, Event)]
+pub struct SensorEvent {
+    pub pawn:       Entity,
+    pub sensor:     Option<Entity>,
+    pub identifier: CollisionMapID,
+    pub transition: SensorTransition,
+}
@@ -0,0 +1,117 @@
+// Copyright 2025 Natalie Baker // AGPLv3 //
+
+use bevy::{math::{BVec2, Vec2}, platform_support::collections::hash_map::HashMap, prelude::{Circle, Entity, Rectangle, Resource}};
+use raydee::prelude::{Ramp, ShapeStatic};
+
+use super::{CollisionFilter, CollisionMap, CollisionMapID};
+
+/// A collider within a tile, offset from the tile's center. Tiles are one unit across.
+#[derive(Debug, Clone, Copy)]
+pub struct TileShape {
+    pub offset: Vec2,
+    pub shape:  ShapeStatic,
+}
+
+impl TileShape {
+
+    #[must_use]
+    pub fn new(offset: Vec2, shape: impl Into<ShapeStatic>) -> Self {
+        Self { offset, shape: shape.into() }
+    }
+
+    /// Mirrors the shape about the tile's center, for tiles flipped in the editor
+    #[must_use]
+    pub fn mirrored(self, axes: BVec2) -> Self {
+        Self {
+            offset: self.offset*Vec2::select(axes, Vec2::NEG_ONE, Vec2::ONE),
+            shape:  self.shape.mirrored(axes),
+        }
+    }
+
+}
+
+/// Maps tileset enum tags, or words in a tile's custom data, onto the shapes to insert for it.
+///
+/// Full squares should keep using the "solid" tag, so they're merged when baked. The built-in
+/// tags are `half_top`, `half_bottom`, `half_left`, `half_right`, `platform`, `circle`, and the
+/// slopes. `slope_ne` and `slope_nw` are floors rising towards the north-east and north-west,
+/// `slope_se` and `slope_sw` are the same slopes flipped vertically into ceilings.
+///
+/// ```
+/// use bevy::math::{BVec2, Vec2};
+/// use game::collision::TileShapeRegistry;
+///
+/// let registry = TileShapeRegistry::default();
+/// let shapes = registry.resolve(["grass", "half_top"]).unwrap();
+/// assert_eq!(shapes[0].offset, Vec2::new(0.0, 0.25));
+///
+/// // A vertically flipped half_top tile is solid along its bottom instead
+/// let flipped = shapes[0].mirrored(BVec2::new(false, true));
+/// assert_eq!(flipped.offset, Vec2::new(0.0, -0.25));
+///
+/// assert!(registry.resolve(["grass"]).is_none());
+/// ```
+#[derive(Debug, Clone, Resource)]
+pub struct TileShapeRegistry {
+    shapes: HashMap<String, Vec<TileShape>>,
+}
+
+impl TileShapeRegistry {
+
+    /// Registry without the built-in tags
+    #[must_use]
+    pub fn empty() -> Self {
+        Self { shapes: HashMap::default() }
+    }
+
+    /// Registers the shapes for a tag, returning those it replaced
+    pub fn register(&mut self, tag: impl Into<String>, shapes: impl IntoIterator<Item = TileShape>) -> Option<Vec<TileShape>> {
+        self.shapes.insert(tag.into(), shapes.into_iter().collect())
+    }
+
+    #[must_use]
+    pub fn get(&self, tag: &str) -> Option<&[TileShape]> {
+        self.shapes.get(tag).map(Vec::as_slice)
+    }
+
+    /// Shapes for the first registered tag
+    pub fn resolve<'a>(&self, tags: impl IntoIterator<Item = &'a str>) -> Option<&[TileShape]> {
+        tags.into_iter().find_map(|tag| self.get(tag))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &[TileShape])> {
+        self.shapes.iter().map(|(k, v)| (k.as_str(), v.as_slice()))
+    }
+
+}
+
+impl Default for TileShapeRegistry {
+    fn default() -> Self {
+        let mut result = Self::empty();
+        result.register("half_top",    [TileShape::new(Vec2::new( 0.0,   0.25), Rectangle::new(1.0, 0.5))]);
+        result.register("half_bottom", [TileShape::new(Vec2::new( 0.0,  -0.25), Rectangle::new(1.0, 0.5))]);
+        result.register("half_left",   [TileShape::new(Vec2::new(-0.25,  0.0 ), Rectangle::new(0.5, 1.0))]);
+        result.register("half_right",  [TileShape::new(Vec2::new( 0.25,  0.0 ), Rectangle::new(0.5, 1.0))]);
+        result.register("platform",    [TileShape::new(Vec2::new( 0.0, 0.4375), Rectangle::new(1.0, 0.125))]);
+        result.register("circle",      [TileShape::new(Vec2::ZERO, Circle::new(0.5))]);
+
+        // Ramps extend from their right-angled corner, so each starts at the corner it's solid in
+        result.register("slope_ne", [TileShape::new(Vec2::new( 0.5, -0.5), Ramp::new_from_size(Vec2::new(-1.0,  1.0)))]);
+        result.register("slope_nw", [TileShape::new(Vec2::new(-0.5, -0.5), Ramp::new_from_size(Vec2::new( 1.0,  1.0)))]);
+        result.register("slope_se", [TileShape::new(Vec2::new( 0.5,  0.5), Ramp::new_from_size(Vec2::new(-1.0, -1.0)))]);
+        result.register("slope_sw", [TileShape::new(Vec2::new(-0.5,  0.5), Ramp::new_from_size(Vec2::new( 1.0, -1.0)))]);
+        result
+    }
+}
+
+impl CollisionMap {
+
+    /// Inserts a tile's shapes as static entries, mirrored by the tile's flip flags
+    pub fn insert_tile(&mut self, center: Vec2, shapes: &[TileShape], flip: BVec2, filter: CollisionFilter, entity: Option<Entity>) -> Vec<CollisionMapID> {
+        shapes.iter()
+            .map(|v| v.mirrored(flip))
+            .map(|v| self.insert(center + v.offset, v.shape, filter, entity))
+            .collect()
+    }
+
+}
@@ -0,0 +1,149 @@
+// Copyright 2025 Natalie Baker // AGPLv3 //
+
+use core::f32::consts::{PI, TAU};
+
+use bevy::{ecs::entity::Entity, math::{IVec2, Vec2}};
+use raydee::prelude::*;
+
+use super::{CollisionFilter, CollisionMap, CollisionMapEntry};
+
+/// Segments per full turn of the rim of a visibility polygon
+const VISIBILITY_SEGMENTS: u32 = 64;
+
+/// Rays are cast either side of each vertex, so they slip past corners onto whatever lies behind
+const VISIBILITY_ANGLE_EPSILON: f32 = 1e-4;
+
+/// A range of directions, ie. a vision cone, as an angle in radians and the full width either side of it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VisibilityArc {
+    pub direction: f32,
+    pub spread:    f32,
+}
+
+impl VisibilityArc {
+
+    #[must_use]
+    pub const fn new(direction: f32, spread: f32) -> Self {
+        Self { direction, spread }
+    }
+
+    #[must_use]
+    pub fn from_direction(direction: Vec2, spread: f32) -> Self {
+        Self::new(direction.to_angle(), spread)
+    }
+
+    #[must_use]
+    pub fn start(&self) -> f32 {
+        self.direction - self.sweep()/2.0
+    }
+
+    #[must_use]
+    pub fn sweep(&self) -> f32 {
+        self.spread.clamp(0.0, TAU)
+    }
+
+}
+
+/// Sensors never block sight, and nor do the ignored entities, ie. the viewer and its target.
+/// Other dynamic entries do unless filtered out.
+fn blocks_sight(entry: &CollisionMapEntry, ignore: &[Entity]) -> bool {
+    !entry.sensor && entry.entity.is_none_or(|v| !ignore.contains(&v))
+}
+
+impl CollisionMap {
+
+    /// Outline of the region visible from the origin, out to the radius, counter-clockwise. When
+    /// limited to an arc the origin is the first point, closing off the cone.
+    ///
+    /// Rays are cast at every corner of the colliders in range, and along the tangents of circles
+    /// and rounded corners, so only the rim is approximated, by 64 segments per turn.
+    #[must_use]
+    pub fn visibility_polygon(&self, origin: Vec2, max_radius: f32, arc: Option<VisibilityArc>, filter: CollisionFilter, ignore: &[Entity]) -> Vec<Vec2> {
+        let (start, sweep) = arc.map_or((-PI, TAU), |v| (v.start(), v.sweep()));
+        let full = sweep >= TAU;
+
+        let rim = ((VISIBILITY_SEGMENTS as f32)*sweep/TAU).ceil().max(1.0) as u32;
+        let mut angles = (0..=rim).map(|i| sweep*(i as f32)/(rim as f32)).collect::<Vec<_>>();
+
+        let reach = Vec2::splat(max_radius);
+        let blockers = self.collect_unique(origin - reach, origin + reach, filter, |entry| blocks_sight(entry, ignore));
+        for entry in &blockers {
+            let rounding = entry.collider.rounding();
+            for vertex in entry.collider.vertices() {
+                let offset   = entry.origin + vertex - origin;
+                let distance = offset.length();
+
+                // Rounded corners are seen along the tangents to their circle, unless the origin is within it
+                if distance <= rounding || distance*distance - rounding*rounding > max_radius*max_radius {
+                    continue;
+                }
+                let spread = (rounding/distance).asin();
+
+                // Sharp corners give the same angle twice, which the dedup below drops
+                let angle = (offset.to_angle() - start).rem_euclid(TAU);
+                for tangent in [angle - spread, angle + spread] {
+                    for angle in [tangent - VISIBILITY_ANGLE_EPSILON, tangent, tangent + VISIBILITY_ANGLE_EPSILON] {
+                        let angle = if full { angle.rem_euclid(TAU) } else { angle };
+                        if (0.0..=sweep).contains(&angle) {
+                            angles.push(angle);
+                        }
+                    }
+                }
+            }
+        }
+
+        angles.sort_by(f32::total_cmp);
+        angles.dedup_by(|a, b| (*a - *b).abs() < VISIBILITY_ANGLE_EPSILON/2.0);
+        if full {
+            // The rim's last sample wraps around onto its first
+            angles.retain(|&v| v < TAU - VISIBILITY_ANGLE_EPSILON/2.0);
+        }
+
+        let mut result = Vec::with_capacity(angles.len() + 1);
+        if !full {
+            result.push(origin);
+        }
+        result.extend(angles.into_iter().map(|angle| {
+            let direction = Vec2::from_angle(start + angle);
+            origin + direction*self.sight_distance(origin, direction, max_radius, filter, ignore)
+        }));
+        result
+    }
+
+    /// True when nothing blocks the straight line from `a` to `b`. Pass the entities standing at
+    /// either end to ignore, so their own colliders don't block the view.
+    ///
+    /// ```
+    /// use bevy::prelude::*;
+    /// use game::collision::{CollisionFilter, CollisionMap};
+    ///
+    /// let mut map = CollisionMap::default();
+    /// map.insert(Vec2::ZERO, Rectangle::new(1.0, 4.0), CollisionFilter::DEFAULT, None);
+    ///
+    /// assert!(!map.line_of_sight(Vec2::new(-2.0, 0.0), Vec2::new(2.0, 0.0), CollisionFilter::DEFAULT, &[]));
+    /// assert!( map.line_of_sight(Vec2::new(-2.0, 3.0), Vec2::new(2.0, 3.0), CollisionFilter::DEFAULT, &[]));
+    ///
+    /// // A pawn standing at b hides itself, unless it's ignored
+    /// let (a, b) = (Vec2::new(-2.0, 3.0), Vec2::new(2.0, 3.0));
+    /// let pawn = Entity::from_raw(1);
+    /// map.insert_dynamic(b, Circle::new(0.5), CollisionFilter::DEFAULT, Some(pawn));
+    ///
+    /// assert!(!map.line_of_sight(a, b, CollisionFilter::DEFAULT, &[]));
+    /// assert!( map.line_of_sight(a, b, CollisionFilter::DEFAULT, &[pawn]));
+    /// ```
+    #[must_use]
+    pub fn line_of_sight(&self, a: Vec2, b: Vec2, filter: CollisionFilter, ignore: &[Entity]) -> bool {
+        let offset   = b - a;
+        let distance = offset.length();
+        distance <= 0.0 || self.sight_distance(a, offset/distance, distance, filter, ignore) >= distance
+    }
+
+    /// Distance along the ray to the first collider that blocks sight, or the max distance
+    fn sight_distance(&self, origin: Vec2, direction: Vec2, max_distance: f32, filter: CollisionFilter, ignore: &[Entity]) -> f32 {
+        let caster = RayCaster::new(origin, direction);
+        self.cast_closest(origin, direction, max_distance, IVec2::ZERO, filter, |entry| {
+            if blocks_sight(entry, ignore) { entry.collider.raycast_enter(entry.origin, &caster) } else { None }
+        }).map_or(max_distance, |v| v.hit.distance)
+    }
+
+}
@@ -0,0 +1,263 @@
+// Copyright 2025 Natalie Baker // AGPLv3 //
+
+use core::{fmt::{self, Display, Write}, str::FromStr};
+use std::path::Path;
+
+use bevy::{platform_support::collections::hash_map::HashMap, prelude::*, reflect::{DynamicEnum, DynamicVariant}};
+use thiserror::Error;
+
+use super::InputAction;
+
+#[derive(Debug, Error)]
+pub enum InputConfigError {
+    #[error("Could not access input config: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Invalid input config on line {line}: {reason}")]
+    Parse {
+        line:   usize,
+        reason: String,
+    },
+}
+
+/// A single input an action can be bound to. Axes are bound by direction, so a stick's halves can
+/// drive opposite actions.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InputBinding {
+    Key(KeyCode),
+    Mouse(MouseButton),
+    GamepadButton(GamepadButton),
+    GamepadAxis(GamepadAxis, f32),
+}
+
+impl Display for InputBinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputBinding::Key(v)                 => write!(f, "key:{v:?}"),
+            InputBinding::Mouse(v)               => write!(f, "mouse:{v:?}"),
+            InputBinding::GamepadButton(v)       => write!(f, "button:{v:?}"),
+            InputBinding::GamepadAxis(v, sign)   => write!(f, "axis:{v:?}{}", if *sign < 0.0 { '-' } else { '+' }),
+        }
+    }
+}
+
+impl FromStr for InputBinding {
+    type Err = String;
+
+    /// As written by [`Display`], ie. `key:KeyW`, `mouse:Left`, `button:South` or `axis:LeftStickY+`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (kind, name) = s.split_once(':').ok_or_else(|| format!("Binding <{s}> is missing its kind"))?;
+        let unknown = || format!("Unknown {kind} <{name}>");
+        match kind {
+            "key"    => parse_variant(name).map(InputBinding::Key).ok_or_else(unknown),
+            "mouse"  => parse_variant(name).map(InputBinding::Mouse).ok_or_else(unknown),
+            "button" => parse_variant(name).map(InputBinding::GamepadButton).ok_or_else(unknown),
+            "axis"   => {
+                let (name, sign) = if let Some(v) = name.strip_suffix('-') { (v, -1.0) } else { (name.strip_suffix('+').unwrap_or(name), 1.0) };
+                parse_variant(name).map(|v| InputBinding::GamepadAxis(v, sign)).ok_or_else(unknown)
+            },
+            _ => Err(format!("Unknown binding kind <{kind}>")),
+        }
+    }
+}
+
+/// Builds a fieldless variant from its name, as printed by its [`Debug`] impl
+fn parse_variant<T: FromReflect>(name: &str) -> Option<T> {
+    T::from_reflect(&DynamicEnum::new(name, DynamicVariant::Unit))
+}
+
+/// Which inputs trigger each [`InputAction`], along with the dead zone for analog sticks.
+///
+/// Saved as plain text, one action per line followed by its bindings, ie.
+/// `move_up = key:KeyW, key:ArrowUp, axis:LeftStickY+`. Lines starting with `#` are ignored.
+#[derive(Debug, Clone, Resource)]
+pub struct InputBindings {
+    bindings:  HashMap<InputAction, Vec<InputBinding>>,
+    dead_zone: f32,
+}
+
+impl Default for InputBindings {
+    fn default() -> Self {
+        use InputBinding::{GamepadAxis as Axis, GamepadButton as Button, Key, Mouse};
+
+        let mut result = Self::empty();
+        result.set(InputAction::MoveUp,    [Key(KeyCode::KeyW), Key(KeyCode::ArrowUp),    Axis(GamepadAxis::LeftStickY,  1.0)]);
+        result.set(InputAction::MoveDown,  [Key(KeyCode::KeyS), Key(KeyCode::ArrowDown),  Axis(GamepadAxis::LeftStickY, -1.0)]);
+        result.set(InputAction::MoveLeft,  [Key(KeyCode::KeyA), Key(KeyCode::ArrowLeft),  Axis(GamepadAxis::LeftStickX, -1.0)]);
+        result.set(InputAction::MoveRight, [Key(KeyCode::KeyD), Key(KeyCode::ArrowRight), Axis(GamepadAxis::LeftStickX,  1.0)]);
+        result.set(InputAction::MoveTo,    [Mouse(MouseButton::Left), Mouse(MouseButton::Right)]);
+        result.set(InputAction::Sprint,    [Key(KeyCode::ShiftLeft), Key(KeyCode::ShiftRight), Button(GamepadButton::RightTrigger2)]);
+        result.set(InputAction::Jump,      [Key(KeyCode::Space), Button(GamepadButton::South)]);
+        result.set(InputAction::Interact,  [Key(KeyCode::KeyE), Button(GamepadButton::West)]);
+        result
+    }
+}
+
+impl InputBindings {
+
+    pub const DEFAULT_DEAD_ZONE: f32 = 0.2;
+
+    /// Largest dead zone, as sticks must have some travel left beyond it to register
+    pub const DEAD_ZONE_MAX: f32 = 0.99;
+
+    /// No bindings at all
+    #[must_use]
+    pub fn empty() -> Self {
+        Self {
+            bindings:  HashMap::default(),
+            dead_zone: Self::DEFAULT_DEAD_ZONE,
+        }
+    }
+
+    #[must_use]
+    pub fn get(&self, action: InputAction) -> &[InputBinding] {
+        self.bindings.get(&action).map_or(&[], Vec::as_slice)
+    }
+
+    /// Replaces the action's bindings
+    pub fn set(&mut self, action: InputAction, bindings: impl IntoIterator<Item = InputBinding>) {
+        self.bindings.insert(action, bindings.into_iter().collect());
+    }
+
+    /// Adds a binding to the action, unless it's already bound
+    pub fn bind(&mut self, action: InputAction, binding: InputBinding) {
+        let bindings = self.bindings.entry(action).or_default();
+        if !bindings.contains(&binding) {
+            bindings.push(binding);
+        }
+    }
+
+    /// Removes a binding from every action it's bound to, ie. before rebinding it elsewhere
+    pub fn unbind(&mut self, binding: InputBinding) {
+        self.bindings.values_mut().for_each(|v| v.retain(|&b| b != binding));
+    }
+
+    /// How far an analog stick must be pushed, from 0 to 1, before it registers
+    #[must_use]
+    pub const fn dead_zone(&self) -> f32 {
+        self.dead_zone
+    }
+
+    /// Clamped from 0 to [`InputBindings::DEAD_ZONE_MAX`]
+    pub const fn set_dead_zone(&mut self, v: f32) {
+        self.dead_zone = v.max(0.0).min(Self::DEAD_ZONE_MAX);
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, InputConfigError> {
+        Self::parse(&std::fs::read_to_string(path)?)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), InputConfigError> {
+        std::fs::write(path, self.to_config())?;
+        Ok(())
+    }
+
+    /// Reads bindings from a config, actions it doesn't mention are left unbound
+    pub fn parse(config: &str) -> Result<Self, InputConfigError> {
+        let mut result = Self::empty();
+        for (i, line) in config.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let error = |reason: String| InputConfigError::Parse{line: i + 1, reason};
+            let (key, value) = line.split_once('=').ok_or_else(|| error("Expected <name = value>".to_owned()))?;
+            let (key, value) = (key.trim(), value.trim());
+
+            if key == "dead_zone" {
+                let dead_zone = value.parse::<f32>().map_err(|_| error(format!("Invalid dead zone <{value}>")))?;
+                if !(0.0..1.0).contains(&dead_zone) {
+                    return Err(error(format!("Dead zone <{value}> must be at least 0 and below 1")));
+                }
+                result.set_dead_zone(dead_zone);
+                continue;
+            }
+
+            let action = InputAction::from_name(key).ok_or_else(|| error(format!("Unknown action <{key}>")))?;
+            let bindings = value.split(',')
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+                .map(str::parse)
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(error)?;
+            result.set(action, bindings);
+        }
+        Ok(result)
+    }
+
+    #[must_use]
+    pub fn to_config(&self) -> String {
+        let mut result = format!("dead_zone = {}\n", self.dead_zone);
+        for action in InputAction::ALL {
+            let bindings = self.get(action).iter().map(ToString::to_string).collect::<Vec<_>>();
+            let _ = writeln!(result, "{} = {}", action.name(), bindings.join(", "));
+        }
+        result
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+
+    use super::{InputBinding, InputBindings, InputConfigError};
+    use crate::input::InputAction;
+
+    fn parse_error_line(config: &str) -> usize {
+        match InputBindings::parse(config) {
+            Err(InputConfigError::Parse{line, ..}) => line,
+            other => panic!("expected a parse error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn config_round_trips() {
+        let mut bindings = InputBindings::default();
+        bindings.set_dead_zone(0.35);
+        bindings.set(InputAction::Interact, []);
+        bindings.bind(InputAction::Jump, InputBinding::GamepadAxis(GamepadAxis::RightStickY, -1.0));
+
+        let parsed = InputBindings::parse(&bindings.to_config()).unwrap();
+        assert!((parsed.dead_zone() - 0.35).abs() < 1e-6);
+        for action in InputAction::ALL {
+            assert_eq!(parsed.get(action), bindings.get(action), "{}", action.name());
+        }
+        assert_eq!(parsed.to_config(), bindings.to_config());
+    }
+
+    #[test]
+    fn comments_and_blank_lines_are_skipped() {
+        let parsed = InputBindings::parse("# bindings\n\n  jump = key:Space  \n").unwrap();
+        assert_eq!(parsed.get(InputAction::Jump), [InputBinding::Key(KeyCode::Space)]);
+        assert!(parsed.get(InputAction::MoveUp).is_empty());
+    }
+
+    #[test]
+    fn malformed_lines_report_their_line() {
+        assert_eq!(parse_error_line("jump = key:Space\njump key:Space"), 2);
+        assert_eq!(parse_error_line("# comment\nfly = key:Space"), 2);
+        assert_eq!(parse_error_line("jump = key:NotAKey"), 1);
+        assert_eq!(parse_error_line("jump = Space"), 1);
+        assert_eq!(parse_error_line("jump = pedal:Left"), 1);
+        assert_eq!(parse_error_line("\n\ndead_zone = lots"), 3);
+    }
+
+    #[test]
+    fn dead_zone_must_be_in_range() {
+        for value in ["1", "1.5", "-0.1", "NaN", "inf"] {
+            assert_eq!(parse_error_line(&format!("dead_zone = {value}")), 1, "{value}");
+        }
+        assert!(InputBindings::parse("dead_zone = 0").is_ok());
+        assert!(InputBindings::parse("dead_zone = 0.9").is_ok());
+
+        let mut bindings = InputBindings::empty();
+        bindings.set_dead_zone(1.0);
+        assert!(bindings.dead_zone() < 1.0);
+        bindings.set_dead_zone(-1.0);
+        assert!(bindings.dead_zone() >= 0.0);
+        bindings.set_dead_zone(f32::NAN);
+        assert!(bindings.dead_zone() >= 0.0);
+    }
+}
@@ -0,0 +1,200 @@
+// Copyright 2025 Natalie Baker // AGPLv3 //
+
+use bevy::{input::InputSystem, prelude::*};
+
+mod bindings;
+pub use bindings::*;
+
+/// Loads [`InputBindings`] from the config file, when given and present, and keeps every
+/// [`InputActions`] up to date with them
+#[derive(Debug, Default, Clone)]
+pub struct PluginInput {
+    pub config: Option<String>,
+}
+
+impl Plugin for PluginInput {
+    fn build(&self, app: &mut App) {
+        let bindings = self.config.as_ref()
+            .filter(|v| std::path::Path::new(v).exists())
+            .and_then(|path| InputBindings::load(path).inspect_err(|e| bevy::log::warn!("{e}")).ok())
+            .unwrap_or_default();
+
+        app
+            .insert_resource(bindings)
+            .add_systems(PreUpdate, input_actions_update.after(InputSystem));
+    }
+}
+
+/// Something a player can do, independent of the inputs bound to it
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum InputAction {
+    MoveUp,
+    MoveDown,
+    MoveLeft,
+    MoveRight,
+    /// Walk towards the cursor
+    MoveTo,
+    Sprint,
+    Jump,
+    Interact,
+}
+
+impl InputAction {
+
+    pub const ALL: [Self; 8] = [
+        Self::MoveUp, Self::MoveDown, Self::MoveLeft, Self::MoveRight,
+        Self::MoveTo, Self::Sprint, Self::Jump, Self::Interact,
+    ];
+
+    /// The action's name in config files
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::MoveUp    => "move_up",
+            Self::MoveDown  => "move_down",
+            Self::MoveLeft  => "move_left",
+            Self::MoveRight => "move_right",
+            Self::MoveTo    => "move_to",
+            Self::Sprint    => "sprint",
+            Self::Jump      => "jump",
+            Self::Interact  => "interact",
+        }
+    }
+
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|v| v.name() == name)
+    }
+
+}
+
+/// Which inputs an [`InputActions`] reads from, so local players each get their own
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum InputDevice {
+    /// Every keyboard, mouse and gamepad
+    #[default]
+    Any,
+    KeyboardMouse,
+    /// The gamepad entity
+    Gamepad(Entity),
+    /// Nothing, ie. a player waiting for a gamepad
+    None,
+}
+
+impl InputDevice {
+
+    #[must_use]
+    pub const fn reads_keyboard_mouse(self) -> bool {
+        matches!(self, Self::Any | Self::KeyboardMouse)
+    }
+
+    #[must_use]
+    pub fn reads_gamepad(self, gamepad: Entity) -> bool {
+        match self {
+            Self::Any        => true,
+            Self::Gamepad(v) => v == gamepad,
+            Self::KeyboardMouse | Self::None => false,
+        }
+    }
+
+}
+
+/// How strongly each action is held as of this frame, from 0 to 1, read by player systems in
+/// place of raw inputs
+#[derive(Debug, Default, Clone, Component)]
+pub struct InputActions {
+    device:   InputDevice,
+    values:   [f32; InputAction::ALL.len()],
+    movement: Vec2,
+}
+
+impl InputActions {
+
+    /// Analog values at or above this count as pressed
+    pub const PRESS_THRESHOLD: f32 = 0.5;
+
+    #[must_use]
+    pub fn new(device: InputDevice) -> Self {
+        Self { device, ..Self::default() }
+    }
+
+    #[must_use]
+    pub const fn device(&self) -> InputDevice {
+        self.device
+    }
+
+    pub const fn set_device(&mut self, v: InputDevice) {
+        self.device = v;
+    }
+
+    #[must_use]
+    pub const fn value(&self, action: InputAction) -> f32 {
+        self.values[action as usize]
+    }
+
+    #[must_use]
+    pub fn pressed(&self, action: InputAction) -> bool {
+        self.value(action) >= Self::PRESS_THRESHOLD
+    }
+
+    /// The move actions combined, with the dead zone removed and a length of at most 1
+    #[must_use]
+    pub const fn movement(&self) -> Vec2 {
+        self.movement
+    }
+
+    /// Sets the actions from the bindings' inputs on the device, the strongest binding of each action winning
+    pub fn update(&mut self, bindings: &InputBindings, keys: &ButtonInput<KeyCode>, mouse: &ButtonInput<MouseButton>, gamepads: &[(Entity, &Gamepad)]) {
+        let dead_zone = bindings.dead_zone();
+        let device    = self.device;
+        let gamepads  = gamepads.iter().filter(|v| device.reads_gamepad(v.0)).map(|v| v.1).collect::<Vec<_>>();
+        let keyboard_mouse = device.reads_keyboard_mouse();
+
+        for action in InputAction::ALL {
+            self.values[action as usize] = bindings.get(action).iter()
+                .map(|&binding| Self::read(binding, keyboard_mouse, keys, mouse, &gamepads))
+                .fold(0.0, f32::max);
+        }
+
+        let movement = Vec2::new(
+            self.value(InputAction::MoveRight) - self.value(InputAction::MoveLeft),
+            self.value(InputAction::MoveUp)    - self.value(InputAction::MoveDown),
+        );
+
+        // Radial dead zone, rescaled so movement starts from zero at its edge
+        let length = movement.length();
+        self.movement = if length <= dead_zone {
+            Vec2::ZERO
+        } else {
+            movement/length*((length - dead_zone)/(1.0 - dead_zone)).min(1.0)
+        };
+    }
+
+    fn read(binding: InputBinding, keyboard_mouse: bool, keys: &ButtonInput<KeyCode>, mouse: &ButtonInput<MouseButton>, gamepads: &[&Gamepad]) -> f32 {
+        let digital = |v: bool| if v { 1.0 } else { 0.0 };
+        match binding {
+            InputBinding::Key(v)   => digital(keyboard_mouse && keys.pressed(v)),
+            InputBinding::Mouse(v) => digital(keyboard_mouse && mouse.pressed(v)),
+            InputBinding::GamepadButton(v) => gamepads.iter()
+                .map(|g| g.get(v).unwrap_or_else(|| digital(g.pressed(v))))
+                .fold(0.0, f32::max),
+            InputBinding::GamepadAxis(v, sign) => gamepads.iter()
+                .map(|g| (g.get(v).unwrap_or(0.0)*sign).max(0.0))
+                .fold(0.0, f32::max),
+        }
+    }
+
+}
+
+pub fn input_actions_update(
+    mut q_actions: Query<&mut InputActions>,
+    q_gamepads: Query<(Entity, &Gamepad)>,
+    r_bindings: Res<InputBindings>,
+    r_keys: Res<ButtonInput<KeyCode>>,
+    r_mouse: Res<ButtonInput<MouseButton>>,
+) {
+    let gamepads = q_gamepads.iter().collect::<Vec<_>>();
+    for mut actions in &mut q_actions {
+        actions.update(&r_bindings, &r_keys, &r_mouse, &gamepads);
+    }
+}
@@ -1,7 +1,11 @@
 // Copyright 2025 Natalie Baker // AGPLv3 //
 
+pub mod body;
 pub mod collision;
+pub mod input;
 pub mod scale;
+pub mod nav;
 pub mod pawn;
+pub mod platform;
 pub mod player;
 pub mod render;
@@ -4,11 +4,16 @@ use bevy::{image::{ImageLoaderSettings, ImageSampler}, prelude::*};
 
 use bevy_asset_aseprite::AsepriteAssetPlugin;
 use bevy_asset_ldtk::{accessors::LdtkRoot, LDTKAssetPlugin, LDTKProject};
+use raydee::prelude::{ShapeCommon, ShapeMoving};
 
 use game::{
-    collision::CollisionMap,
-    pawn::{sync_pawn_transform, Pawn},
-    player::{player_move_apply, player_move_keeb, player_move_mouse, CameraPlayer, PawnPlayer},
+    body::{DynamicBody, PluginBody},
+    collision::{CollisionBakeReport, CollisionFilter, CollisionGrid, CollisionLayerNames, CollisionMap, LdtkColliderBuilder, PluginCollision, TileShapeRegistry},
+    input::{InputActions, InputDevice, PluginInput},
+    pawn::{Pawn, PawnControlSystems, PawnContacts, PawnController, PawnMotionCache, PawnSensors, PluginPawn},
+    nav::PluginNav,
+    platform::{PlatformSystems, PluginPlatform},
+    player::{player_move_actions, player_move_apply, player_move_mouse, CameraPlayer, PawnPlayer, PlayerJoined, PlayerSlot, PlayerSlots, PluginPlayerSlots},
     render::{MultiTextureAtlasBuilder, MultiTextureAtlasLoader, PluginMultiTextureAtlas, PluginTilemapMaterial, TilemapMaterial, TilemapMaterialSync},
     scale::{apply_pixel_scale, CameraPixelScaler, PixelsPerUnit},
 };
@@ -25,17 +30,23 @@ fn main() {
         .add_plugins(AsepriteAssetPlugin)
         .add_plugins(PluginMultiTextureAtlas)
         .add_plugins(PluginTilemapMaterial)
+        .add_plugins(PluginCollision{layers: Some("collision_layers.cfg".to_owned())})
+        .add_plugins(PluginPlatform)
+        .add_plugins(PluginBody)
+        .add_plugins(PluginNav)
+        .add_plugins(PluginPawn::default())
+        .add_plugins(PluginInput{config: Some("input.cfg".to_owned())})
+        .add_plugins(PluginPlayerSlots)
         .insert_resource(ClearColor(Srgba::hex("111122").unwrap().into()))
         .insert_resource(PixelsPerUnit(24.0))
-        .insert_resource(CollisionMap::default())
         .add_systems(Startup, setup)
         .add_systems(PreUpdate, setup_map)
-        .add_systems(Update, (
-            player_move_keeb, 
+        .add_systems(Update, spawn_joined_players)
+        .add_systems(FixedUpdate, (
+            player_move_actions, 
             player_move_mouse, 
             player_move_apply
-        ).chain())
-        .add_systems(PostUpdate, sync_pawn_transform)
+        ).chain().after(PlatformSystems).before(PawnControlSystems))
         .add_systems(PostUpdate, apply_pixel_scale)
         .run();
 }
@@ -46,6 +57,16 @@ pub struct GameProjectHandle(Handle<LDTKProject>);
 #[derive(Debug, Component)]
 pub struct LevelDespawnFlag;
 
+/// Where the loaded level spawns players, for those joining later
+#[derive(Debug, Resource)]
+pub struct PlayerSpawnPoint {
+    position: Vec2,
+    collider: ShapeMoving,
+}
+
+/// How many pawn widths either side of the spawn point to look for room for another player
+const PLAYER_SPAWN_SEARCH: i32 = 8;
+
 fn setup_map(    
     mut commands: Commands,
 
@@ -55,11 +76,14 @@ fn setup_map(
     r_assets_ldtk: Res<Assets<LDTKProject>>,    
 
     q_player: Query<&PawnPlayer>,
+    r_player_slots: Res<PlayerSlots>,
     q_despawn: Query<Entity, With<LevelDespawnFlag>>,
 
     r_ppu: Res<PixelsPerUnit>,
     r_map_active: Res<GameProjectHandle>,
     mut r_collision_map: ResMut<CollisionMap>,
+    mut r_collision_layers: ResMut<CollisionLayerNames>,
+    r_tile_shapes: Res<TileShapeRegistry>,
 
     mut r_images: ResMut<Assets<Image>>,
     mut r_meshes: ResMut<Assets<Mesh>>,
@@ -86,6 +110,15 @@ fn setup_map(
     let Some(project) = r_assets_ldtk.get(&r_map_active.0) else { return; };
     let project = LdtkRoot::wrap(project);
     let world   = project.get_world(0).unwrap();
+
+    // // Collision Layers // //
+
+    if let Some(definition) = project.get_enum("collision_layer") {
+        if let Err(e) = r_collision_layers.register_ldtk_enum(definition) {
+            bevy::log::warn!("Failed to register collision layers: {e}");
+        }
+    }
+    let filter_walls = r_collision_layers.layer("walls").map_or(CollisionFilter::DEFAULT, |v| CollisionFilter::DEFAULT.with_memberships(v));
     let Some(level) = world.levels().find(|l| l.identifier() == "level_0") else { panic!("Level data contains no 'level_0' room"); };
 
     // // Tilesets // //
@@ -102,7 +135,8 @@ fn setup_map(
 
         let mut tilemap = TilemapMaterial::new((layer.size_px().as_vec2()/(layer.size_grid() as f32)).ceil().as_uvec2(), None);
 
-        if let Some(tileset) = layer.tileset_uid().and_then(|uid| loader.get(uid)) {
+        let mut solid_cells = Vec::new();
+        if let Some((tileset, tileset_def)) = layer.tileset_uid().and_then(|uid| loader.get(uid)).zip(layer.tileset_def()) {
             for tile in layer.auto_layer_tiles() {
 
                 // TODO check for non-round tile positions and warn
@@ -112,12 +146,28 @@ fn setup_map(
                 
                 tilemap.set_tile(pos_local.as_uvec2(), Some(identifer), tile.flip_x(), tile.flip_y());
 
-                if layer.tileset_def().unwrap().has_enum_tag(tile.uid(), "solid") {
-                    r_collision_map.get_mut(0).insert(pos_world.as_vec2() + Vec2::new(0.5, 0.5), Rectangle::from_size(Vec2::ONE), None);
+                if tileset_def.has_enum_tag(tile.uid(), "solid") {
+                    solid_cells.push(pos_world);
+                    continue;
+                }
+
+                // Anything else is looked up by its tags, then the words in its custom data
+                let custom_data = tileset_def.custom_data(tile.uid()).unwrap_or_default();
+                let tags = tileset_def.enum_tags(tile.uid()).chain(custom_data.split(|c: char| c.is_whitespace() || c == ','));
+                if let Some(shapes) = r_tile_shapes.resolve(tags) {
+                    // flip_y is inverted for the y-up tilemap, the shapes want the editor's flag
+                    let flip = BVec2::new(tile.flip_x(), !tile.flip_y());
+                    r_collision_map.insert_tile(pos_world.as_vec2() + Vec2::splat(0.5), shapes, flip, filter_walls, None);
                 }
             }
         }
 
+        // Merged across the whole layer, so there are no seams for pawns to catch on
+        let grid   = CollisionGrid::from_cells(solid_cells);
+        let rects  = grid.bake();
+        r_collision_map.insert_baked(&rects, Vec2::ZERO, Vec2::ONE, filter_walls);
+        bevy::log::info!("[Level] Collision {}", CollisionBakeReport::from_rects(&rects));
+
         let mesh = tilemap.create_quad_mesh(1.0);
         let material = r_materials.add(tilemap);
         tileset_entity.commands_mut().spawn((
@@ -133,8 +183,17 @@ fn setup_map(
 
     // // Entity Layers // //
 
+    let collider_builder = LdtkColliderBuilder::new(r_ppu.0).with_filter(filter_walls);
+
     if let Some(layer) = level.layers().find(|l| l.identifier() == "entities") {
         for entity in layer.entities() {
+            if collider_builder.matches(&entity) {
+                if let Err(e) = collider_builder.insert(&mut r_collision_map, &entity, &r_collision_layers, None) {
+                    bevy::log::warn!("Failed to build collider: {e}");
+                }
+                continue;
+            }
+
             if entity.identifier() == "spawn_player" {
                 if player_spawned || player_spawned_now {
                     if !player_spawned_now {
@@ -142,8 +201,28 @@ fn setup_map(
                     }
                 } else {
                     player_spawned_now = true;
-                    spawn_player(&mut commands, &r_asset_server, entity.offset_px().as_vec2()/r_ppu.0 + Vec2::splat(0.5));
+                    let collider = Pawn::collider_from_ldtk(&entity, r_ppu.0, "shape");
+                    let position = entity.offset_px().as_vec2()/r_ppu.0 + Vec2::splat(0.5);
+                    commands.insert_resource(PlayerSpawnPoint{position, collider});
+
+                    let mut taken = Vec::new();
+                    for (slot, device) in r_player_slots.iter() {
+                        let Some(spot) = find_player_spawn(&r_collision_map, collider, position, &taken) else {
+                            bevy::log::warn!("No room to spawn player {slot}.");
+                            continue;
+                        };
+                        taken.push(spot);
+                        spawn_player(&mut commands, &r_asset_server, spot, collider, slot, device);
+                    }
                 }
+            } else if entity.identifier() == "crate" {
+                let position = entity.offset_px().as_vec2()/r_ppu.0 + Vec2::splat(0.5);
+                commands.spawn((
+                    DynamicBody::new(position, Rectangle::from_size(Vec2::splat(0.9)), filter_walls),
+                    Sprite::from_color(Srgba::hex("886633").unwrap(), Vec2::splat(0.9)),
+                    Transform::from_translation(position.extend(0.5)),
+                    LevelDespawnFlag,
+                ));
             }
         }
     }
@@ -151,6 +230,46 @@ fn setup_map(
     bevy::log::info!("[Level] Load complete");
 }
 
+/// Spawns pawns for players joining after the level loaded, the level spawns any before
+fn spawn_joined_players(
+    mut commands: Commands,
+    mut ev_joined: EventReader<PlayerJoined>,
+    r_asset_server: Res<AssetServer>,
+    r_player_slots: Res<PlayerSlots>,
+    r_spawn: Option<Res<PlayerSpawnPoint>>,
+    r_collision_map: Res<CollisionMap>,
+) {
+    let Some(spawn) = r_spawn else {
+        ev_joined.clear();
+        return;
+    };
+
+    let mut taken = Vec::new();
+    for PlayerJoined{slot} in ev_joined.read().copied() {
+        let Some(device) = r_player_slots.device(slot) else { continue; };
+        let Some(spot) = find_player_spawn(&r_collision_map, spawn.collider, spawn.position, &taken) else {
+            bevy::log::warn!("No room to spawn player {slot}.");
+            continue;
+        };
+        taken.push(spot);
+        spawn_player(&mut commands, &r_asset_server, spot, spawn.collider, slot, device);
+    }
+}
+
+/// The nearest spot beside the spawn point, a pawn width at a time, where the collider fits and
+/// no other player was just placed
+fn find_player_spawn(colliders: &CollisionMap, collider: ShapeMoving, position: Vec2, taken: &[Vec2]) -> Option<Vec2> {
+    let [min, max] = collider.bounding_box();
+    let width = max.x - min.x;
+
+    (0..=PLAYER_SPAWN_SEARCH)
+        .flat_map(|i| [i, -i])
+        .skip(1)
+        .map(|i| position + Vec2::X*(width*(i as f32)))
+        .filter(|&spot| taken.iter().all(|v| v.distance(spot) >= width))
+        .find(|&spot| Pawn::fits(colliders, Entity::PLACEHOLDER, collider, spot, CollisionFilter::DEFAULT))
+}
+
 fn setup(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
@@ -161,7 +280,10 @@ fn setup(
 pub fn spawn_player(
     commands: &mut Commands,
     assets: &AssetServer,
-    position: Vec2
+    position: Vec2,
+    collider: ShapeMoving,
+    slot: usize,
+    device: InputDevice,
 ) {
     commands.spawn((
         PawnPlayer{
@@ -179,12 +301,19 @@ pub fn spawn_player(
             custom_size: Some(Vec2::ONE),
             ..default()
         },
-        Pawn::new(position, 1.0, 0x01),
+        Pawn::new(position, collider, CollisionFilter::DEFAULT),
+        PawnController::new(4.0*3.6),
+        InputActions::new(device),
+        PlayerSlot(slot),
+        PawnMotionCache::default(),
+        PawnSensors::default(),
+        PawnContacts::default(),
         Transform::from_translation(position.extend(0.0)),
         InheritedVisibility::VISIBLE
     )).with_child((
         Camera2d,
         CameraPlayer,
+        PlayerSlot(slot),
         CameraPixelScaler{
             size_target_units: Vec2::ONE*15.0
         }
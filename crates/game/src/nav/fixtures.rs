@@ -0,0 +1,32 @@
+// Copyright 2025 Natalie Baker // AGPLv3 //
+
+use bevy::math::{primitives::Rectangle, Vec2};
+
+use crate::collision::{CollisionFilter, CollisionMap, CollisionMapID};
+
+/// An open 8x8 room
+pub fn room() -> CollisionMap {
+    let mut map = CollisionMap::default();
+    let walls = [
+        (Vec2::new(-1.0, -1.0), Vec2::new(0.0, 9.0)),
+        (Vec2::new( 8.0, -1.0), Vec2::new(9.0, 9.0)),
+        (Vec2::new(-1.0, -1.0), Vec2::new(9.0, 0.0)),
+        (Vec2::new(-1.0,  8.0), Vec2::new(9.0, 9.0)),
+    ];
+    for (min, max) in walls {
+        wall(&mut map, min, max);
+    }
+    map
+}
+
+/// The room split down the middle by a wall, with a one unit gap at y 4..5
+pub fn split_room() -> CollisionMap {
+    let mut map = room();
+    wall(&mut map, Vec2::new(4.0, 0.0), Vec2::new(5.0, 4.0));
+    wall(&mut map, Vec2::new(4.0, 5.0), Vec2::new(5.0, 8.0));
+    map
+}
+
+pub fn wall(map: &mut CollisionMap, min: Vec2, max: Vec2) -> CollisionMapID {
+    map.insert(min.midpoint(max), Rectangle::from_corners(min, max), CollisionFilter::DEFAULT, None)
+}
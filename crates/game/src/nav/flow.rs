@@ -0,0 +1,325 @@
+// Copyright 2025 Natalie Baker // AGPLv3 //
+
+use std::collections::BinaryHeap;
+
+use bevy::{math::{IVec2, UVec2, Vec2}, prelude::Component};
+
+use super::{path::OpenCell, NavGrid};
+
+/// Distance to the nearest goal for every cell of a [`NavGrid`], and the direction to step in to
+/// get closer, so any number of pawns can share a single search. A field on a pawn targets that
+/// pawn, see [`super::flow_field_update`]. Fields are searched on the [`super::NavGrids`] grid
+/// for their radius, which should fit the pawns following them.
+///
+/// Goal and cell changes are patched in place. Removing goals or blocking cells clears every
+/// distance routed through them, then refills those from the cells bordering the cleared area,
+/// so the cost follows how much of the field depended on them. A lone goal moving still
+/// touches every cell it reaches. Only resizing or falling behind the grid's changes searches afresh.
+#[derive(Debug, Clone, Component)]
+pub struct FlowField {
+    radius:       f32,
+    goals:        Vec<IVec2>,
+    goals_applied: Vec<IVec2>,
+    min:          IVec2,
+    size:         UVec2,
+    grid_version: Option<u64>,
+    costs:        Vec<f32>,
+    /// Index of the neighbour each cell's distance was reached through, None for goals and unreachable cells
+    parents:      Vec<Option<usize>>,
+    directions:   Vec<Vec2>,
+}
+
+impl Default for FlowField {
+    fn default() -> Self {
+        Self {
+            radius:       Self::DEFAULT_RADIUS,
+            goals:        Vec::new(),
+            goals_applied: Vec::new(),
+            min:          IVec2::ZERO,
+            size:         UVec2::ZERO,
+            grid_version: None,
+            costs:        Vec::new(),
+            parents:      Vec::new(),
+            directions:   Vec::new(),
+        }
+    }
+}
+
+impl FlowField {
+
+    pub const DEFAULT_RADIUS: f32 = 0.5;
+
+    #[must_use]
+    pub fn new(goals: impl IntoIterator<Item = IVec2>) -> Self {
+        let mut result = Self::default();
+        result.set_goals(goals);
+        result
+    }
+
+    /// Clearance the field is searched with, ie. the largest follower's [`crate::pawn::Pawn::radius`]
+    #[must_use]
+    pub const fn with_radius(mut self, radius: f32) -> Self {
+        self.radius = radius;
+        self
+    }
+
+    #[must_use]
+    pub const fn radius(&self) -> f32 {
+        self.radius
+    }
+
+    #[must_use]
+    pub fn goals(&self) -> &[IVec2] {
+        &self.goals
+    }
+
+    /// Replaces the goal cells, taking effect on the next [`FlowField::update`]
+    pub fn set_goals(&mut self, goals: impl IntoIterator<Item = IVec2>) {
+        self.goals = goals.into_iter().collect();
+    }
+
+    /// Distance to the nearest goal, infinite when unreachable or outside the field
+    #[must_use]
+    pub fn cost(&self, cell: IVec2) -> f32 {
+        self.index(cell).map_or(f32::INFINITY, |i| self.costs[i])
+    }
+
+    /// Normalized direction towards the neighbour closest to a goal, zero at goals or when unreachable
+    #[must_use]
+    pub fn direction(&self, cell: IVec2) -> Vec2 {
+        self.index(cell).map_or(Vec2::ZERO, |i| self.directions[i])
+    }
+
+    /// The neighbour to step into from the cell, if any
+    #[must_use]
+    pub fn next_cell(&self, cell: IVec2) -> Option<IVec2> {
+        let direction = self.direction(cell);
+        (direction != Vec2::ZERO).then(|| cell + direction.round().as_ivec2())
+    }
+
+    /// Brings the field up to date with its goals and the grid, returning false when it already was
+    pub fn update(&mut self, grid: &NavGrid) -> bool {
+        let resized = self.min != grid.min() || self.size != grid.size();
+        let grid_changed = self.grid_version != Some(grid.version());
+        if !grid_changed && self.goals == self.goals_applied {
+            return false;
+        }
+
+        // Only the grid's latest changes are known, anything older needs a fresh search
+        let patchable = !resized && self.grid_version.is_some_and(|v| {
+            v >= grid.version_rebuilt() && v + 1 >= grid.version()
+        });
+
+        if patchable {
+            self.repair(grid, grid_changed);
+        } else {
+            self.recompute(grid);
+        }
+
+        self.goals_applied.clone_from(&self.goals);
+        self.grid_version = Some(grid.version());
+        self.update_directions(grid);
+        true
+    }
+
+    fn recompute(&mut self, grid: &NavGrid) {
+        self.min  = grid.min();
+        self.size = grid.size();
+
+        let count = (self.size.x as usize)*(self.size.y as usize);
+        self.costs = vec![f32::INFINITY; count];
+        self.parents = vec![None; count];
+        self.directions = vec![Vec2::ZERO; count];
+
+        let seeds = self.seed_goals(grid);
+        self.relax(grid, seeds);
+    }
+
+    /// Patches the field for the goals and cells changed since the last update
+    fn repair(&mut self, grid: &NavGrid, grid_changed: bool) {
+        let changes = if grid_changed { grid.changes() } else { &[] };
+
+        // Distances through removed goals or blocked cells no longer hold
+        let mut roots = self.goals_applied.iter().copied().filter(|v| !self.goals.contains(v)).collect::<Vec<_>>();
+        for &(cell, _) in changes.iter().filter(|v| !v.1) {
+            roots.push(cell);
+
+            // Including diagonal steps that cut the blocked cell's corner
+            roots.extend(Self::around(cell).filter(|&v| self.index(v).and_then(|i| self.parents[i]).is_some_and(|j| {
+                let parent = self.cell_at(j);
+                !grid.neighbours(parent).any(|n| n.0 == v)
+            })));
+        }
+        let cleared = self.clear_subtrees(roots);
+
+        // Refill the cleared cells from what still reaches them, along with new goals and opened cells
+        let mut seeds = self.seed_goals(grid);
+        for &cell in cleared.iter().chain(changes.iter().filter(|v| v.1).map(|v| &v.0)) {
+            seeds.extend(grid.neighbours(cell).map(|v| v.0).filter(|&v| self.cost(v).is_finite()));
+        }
+
+        self.relax(grid, seeds);
+    }
+
+    /// Zeroes the walkable goals not already at zero, returning them
+    fn seed_goals(&mut self, grid: &NavGrid) -> Vec<IVec2> {
+        let mut seeds = Vec::new();
+        for &cell in &self.goals {
+            let Some(i) = self.index(cell).filter(|_| grid.is_walkable(cell)) else { continue; };
+            if self.costs[i] > 0.0 {
+                self.costs[i]   = 0.0;
+                self.parents[i] = None;
+                seeds.push(cell);
+            }
+        }
+        seeds
+    }
+
+    /// Clears the distances of the cells and every cell reached through them, returning those cleared
+    fn clear_subtrees(&mut self, roots: Vec<IVec2>) -> Vec<IVec2> {
+        let mut cleared = Vec::new();
+        let mut open    = roots;
+        while let Some(cell) = open.pop() {
+            let Some(i) = self.index(cell) else { continue; };
+            if self.costs[i].is_infinite() {
+                continue;
+            }
+
+            self.costs[i]   = f32::INFINITY;
+            self.parents[i] = None;
+            cleared.push(cell);
+
+            // Blocked cells are no longer neighbours on the grid, so walk the raw offsets
+            open.extend(Self::around(cell).filter(|&v| self.index(v).is_some_and(|j| self.parents[j] == Some(i))));
+        }
+        cleared
+    }
+
+    /// Dijkstra from the seeds, only ever lowering distances
+    fn relax(&mut self, grid: &NavGrid, seeds: Vec<IVec2>) {
+        let mut open = seeds.into_iter().map(|cell| OpenCell{cost: self.cost(cell), cell}).collect::<BinaryHeap<_>>();
+        while let Some(OpenCell{cost, cell}) = open.pop() {
+            if cost > self.cost(cell) {
+                continue;
+            }
+
+            let parent = self.index(cell);
+            for (next, step) in grid.neighbours(cell) {
+                let cost_next = cost + step;
+                let Some(i) = self.index(next) else { continue; };
+                if cost_next < self.costs[i] {
+                    self.costs[i]   = cost_next;
+                    self.parents[i] = parent;
+                    open.push(OpenCell{cost: cost_next, cell: next});
+                }
+            }
+        }
+    }
+
+    fn update_directions(&mut self, grid: &NavGrid) {
+        for i in 0..self.costs.len() {
+            let cell = self.cell_at(i);
+            let best = grid.neighbours(cell)
+                .map(|(next, step)| (next, self.cost(next) + step))
+                .filter(|&(_, cost)| cost < self.costs[i])
+                .min_by(|a, b| a.1.total_cmp(&b.1));
+
+            self.directions[i] = best.map_or(Vec2::ZERO, |(next, _)| (next - cell).as_vec2().normalize());
+        }
+    }
+
+    /// The cell and its 8 neighbours
+    fn around(cell: IVec2) -> impl Iterator<Item = IVec2> {
+        (-1..=1).flat_map(move |y| (-1..=1).map(move |x| cell + IVec2::new(x, y)))
+    }
+
+    fn cell_at(&self, index: usize) -> IVec2 {
+        self.min + IVec2::new((index % self.size.x as usize) as i32, (index / self.size.x as usize) as i32)
+    }
+
+    fn index(&self, cell: IVec2) -> Option<usize> {
+        let local = cell - self.min;
+        (local.cmpge(IVec2::ZERO).all() && local.as_uvec2().cmplt(self.size).all())
+            .then(|| (local.y as usize)*(self.size.x as usize) + (local.x as usize))
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::math::{IVec2, Vec2};
+
+    use crate::{collision::CollisionFilter, nav::fixtures::{room, wall}};
+
+    use super::{FlowField, NavGrid};
+
+    fn assert_matches_fresh(field: &FlowField, grid: &NavGrid) {
+        let mut fresh = FlowField::new(field.goals().iter().copied()).with_radius(field.radius());
+        fresh.update(grid);
+
+        for y in 0..grid.size().y as i32 {
+            for x in 0..grid.size().x as i32 {
+                let cell = grid.min() + IVec2::new(x, y);
+                let (patched, expected) = (field.cost(cell), fresh.cost(cell));
+                assert!(
+                    (patched.is_infinite() && expected.is_infinite()) || (patched - expected).abs() < 1e-4,
+                    "{cell}: patched {patched}, fresh {expected}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn moving_goals_matches_a_fresh_search() {
+        let map = room();
+        let mut grid = NavGrid::new(0.5, 0.25, CollisionFilter::DEFAULT);
+        grid.rebuild(&map);
+
+        let mut field = FlowField::new([grid.cell_of(Vec2::new(2.0, 2.0))]).with_radius(0.25);
+        field.update(&grid);
+
+        // A pawn target walking across the room, a cell at a time
+        for x in 5..12 {
+            field.set_goals([IVec2::new(x, 4)]);
+            assert!(field.update(&grid));
+            assert_matches_fresh(&field, &grid);
+        }
+
+        field.set_goals([IVec2::new(11, 4), IVec2::new(3, 12), IVec2::new(13, 13)]);
+        field.update(&grid);
+        assert_matches_fresh(&field, &grid);
+
+        field.set_goals([IVec2::new(3, 12), IVec2::new(6, 6)]);
+        field.update(&grid);
+        assert_matches_fresh(&field, &grid);
+    }
+
+    #[test]
+    fn blocking_and_opening_cells_matches_a_fresh_search() {
+        let mut map  = room();
+        let mut grid = NavGrid::new(0.5, 0.25, CollisionFilter::DEFAULT);
+        grid.rebuild(&map);
+
+        let mut field = FlowField::new([grid.cell_of(Vec2::new(1.0, 4.0))]).with_radius(0.25);
+        field.update(&grid);
+
+        // A wall across most of the room, with diagonals around its ends cut off
+        let id = wall(&mut map, Vec2::new(3.0, 0.0), Vec2::new(3.5, 6.5));
+        assert!(grid.update(&map));
+        assert!(grid.version_rebuilt() < grid.version(), "the grid should be patched, not rebuilt");
+        assert!(field.update(&grid));
+        assert_matches_fresh(&field, &grid);
+
+        // Blocking and moving the goal in the same update
+        wall(&mut map, Vec2::new(5.0, 2.0), Vec2::new(7.0, 2.5));
+        grid.update(&map);
+        field.set_goals([grid.cell_of(Vec2::new(6.0, 6.0))]);
+        field.update(&grid);
+        assert_matches_fresh(&field, &grid);
+
+        map.remove(id);
+        grid.update(&map);
+        field.update(&grid);
+        assert_matches_fresh(&field, &grid);
+    }
+}
@@ -0,0 +1,317 @@
+// Copyright 2025 Natalie Baker // AGPLv3 //
+
+use bevy::{math::{IVec2, UVec2, Vec2}, platform_support::collections::hash_map::HashMap, prelude::{Circle, Resource}};
+use raydee::prelude::*;
+
+use crate::{collision::{CollisionFilter, CollisionMap, CollisionMapEntry, CollisionMapID}, pawn::Pawn};
+
+/// A [`NavGrid`] per clearance radius, so pawns only path through gaps they fit. Each pawn uses
+/// the smallest grid at least as wide as its collider, see [`NavGrids::for_pawn`].
+#[derive(Debug, Clone, Resource)]
+pub struct NavGrids {
+    grids: Vec<NavGrid>,
+}
+
+impl Default for NavGrids {
+    fn default() -> Self {
+        Self::new(NavGrid::DEFAULT_CELL_SIZE, Self::DEFAULT_RADII, CollisionFilter::DEFAULT)
+    }
+}
+
+impl NavGrids {
+
+    pub const DEFAULT_RADII: [f32; 3] = [0.25, 0.5, 1.0];
+
+    /// One grid per radius, which must be given at least one
+    #[must_use]
+    pub fn new(cell_size: f32, radii: impl IntoIterator<Item = f32>, filter: CollisionFilter) -> Self {
+        let mut grids = radii.into_iter().map(|v| NavGrid::new(cell_size, v, filter)).collect::<Vec<_>>();
+        assert!(!grids.is_empty(), "NavGrids needs at least one radius");
+        grids.sort_by(|a, b| a.radius().total_cmp(&b.radius()));
+        Self { grids }
+    }
+
+    /// The smallest grid fitting the radius, or the largest when none do
+    #[must_use]
+    pub fn for_radius(&self, radius: f32) -> &NavGrid {
+        self.grids.iter().find(|v| v.radius() >= radius).unwrap_or_else(|| &self.grids[self.grids.len() - 1])
+    }
+
+    /// The grid fitting the circle bounding the pawn's collider
+    #[must_use]
+    pub fn for_pawn(&self, pawn: &Pawn) -> &NavGrid {
+        self.for_radius(pawn.radius())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &NavGrid> {
+        self.grids.iter()
+    }
+
+    /// Updates every grid, see [`NavGrid::update`]. Returns false when there was nothing to do.
+    pub fn update(&mut self, colliders: &CollisionMap) -> bool {
+        self.grids.iter_mut().fold(false, |changed, v| v.update(colliders) || changed)
+    }
+
+}
+
+/// Cells a pawn of the grid's radius can stand in without touching static colliders.
+///
+/// Cell centers lie on multiples of the cell size, so with the default half-unit cells they
+/// land on both tile centers and tile edges.
+///
+/// [`NavGrid::update`] only re-tests the cells around colliders added or removed since the last
+/// update, recording the cells that changed so [`super::FlowField`]s can be patched rather than
+/// recomputed.
+#[derive(Debug, Clone)]
+pub struct NavGrid {
+    min:        IVec2,
+    size:       UVec2,
+    cell_size:  f32,
+    radius:     f32,
+    filter:     CollisionFilter,
+    revision:   Option<u64>,
+    generation: u64,
+    version:    u64,
+    version_rebuilt: u64,
+    walkable:   Vec<bool>,
+    changes:    Vec<(IVec2, bool)>,
+    entries:    HashMap<CollisionMapID, [Vec2; 2]>,
+}
+
+impl NavGrid {
+
+    pub const DEFAULT_CELL_SIZE: f32 = 0.5;
+
+    /// How much the radius is shrunk when testing cells, so pawns fit gaps exactly their size
+    pub const CLEARANCE_TOLERANCE: f32 = 0.05;
+
+    /// Empty grid, see [`NavGrid::rebuild`]
+    #[must_use]
+    pub fn new(cell_size: f32, radius: f32, filter: CollisionFilter) -> Self {
+        Self {
+            min: IVec2::ZERO,
+            size: UVec2::ZERO,
+            cell_size,
+            radius,
+            filter,
+            revision: None,
+            generation: 0,
+            version: 0,
+            version_rebuilt: 0,
+            walkable: Vec::new(),
+            changes: Vec::new(),
+            entries: HashMap::default(),
+        }
+    }
+
+    #[must_use]
+    pub const fn cell_size(&self) -> f32 {
+        self.cell_size
+    }
+
+    #[must_use]
+    pub const fn radius(&self) -> f32 {
+        self.radius
+    }
+
+    #[must_use]
+    pub const fn filter(&self) -> CollisionFilter {
+        self.filter
+    }
+
+    #[must_use]
+    pub const fn min(&self) -> IVec2 {
+        self.min
+    }
+
+    #[must_use]
+    pub const fn size(&self) -> UVec2 {
+        self.size
+    }
+
+    /// The [`CollisionMap::revision`] the grid was last built from
+    #[must_use]
+    pub const fn revision(&self) -> Option<u64> {
+        self.revision
+    }
+
+    /// Bumped whenever any cell might have changed
+    #[must_use]
+    pub const fn version(&self) -> u64 {
+        self.version
+    }
+
+    /// The version of the last full rebuild, which can move or resize the grid
+    #[must_use]
+    pub const fn version_rebuilt(&self) -> u64 {
+        self.version_rebuilt
+    }
+
+    /// Cells whose walkability changed in the last update, and whether they're now walkable.
+    /// Empty after a full rebuild.
+    #[must_use]
+    pub fn changes(&self) -> &[(IVec2, bool)] {
+        &self.changes
+    }
+
+    #[must_use]
+    pub fn cell_of(&self, point: Vec2) -> IVec2 {
+        (point/self.cell_size).round().as_ivec2()
+    }
+
+    #[must_use]
+    pub fn cell_center(&self, cell: IVec2) -> Vec2 {
+        cell.as_vec2()*self.cell_size
+    }
+
+    #[must_use]
+    pub fn contains(&self, cell: IVec2) -> bool {
+        self.index(cell).is_some()
+    }
+
+    /// Cells outside the grid are never walkable
+    #[must_use]
+    pub fn is_walkable(&self, cell: IVec2) -> bool {
+        self.index(cell).is_some_and(|i| self.walkable[i])
+    }
+
+    /// Walkable cells around the cell, with their step costs. Diagonals can't cut corners.
+    pub fn neighbours(&self, cell: IVec2) -> impl Iterator<Item = (IVec2, f32)> + '_ {
+        const STEPS: [IVec2; 8] = [
+            IVec2::new( 1,  0), IVec2::new(-1,  0), IVec2::new( 0,  1), IVec2::new( 0, -1),
+            IVec2::new( 1,  1), IVec2::new(-1,  1), IVec2::new( 1, -1), IVec2::new(-1, -1),
+        ];
+
+        STEPS.into_iter().filter_map(move |step| {
+            let next = cell + step;
+            let diagonal = step.x != 0 && step.y != 0;
+            let walkable = self.is_walkable(next) && (!diagonal || (
+                self.is_walkable(cell + IVec2::new(step.x, 0)) &&
+                self.is_walkable(cell + IVec2::new(0, step.y))
+            ));
+            walkable.then_some((next, if diagonal { core::f32::consts::SQRT_2 } else { 1.0 }))
+        })
+    }
+
+    /// Brings the grid up to date with the map, re-testing only the cells around colliders added
+    /// or removed since the last update. Returns false when there was nothing to do.
+    pub fn update(&mut self, colliders: &CollisionMap) -> bool {
+        if self.revision == Some(colliders.revision()) {
+            return false;
+        }
+
+        // Cleared or grown beyond the grid, nothing to patch
+        let covered = colliders.chunk_bounds().is_none_or(|[min, max]| {
+            let [min, max] = Self::chunk_bounds_to_world([min, max]);
+            self.contains(self.cell_of(min)) && self.contains(self.cell_of(max))
+        });
+        if self.revision.is_none() || self.generation != colliders.generation() || !covered {
+            self.rebuild(colliders);
+            return true;
+        }
+
+        self.revision = Some(colliders.revision());
+        self.version += 1;
+        self.changes.clear();
+
+        let mut current = HashMap::<CollisionMapID, [Vec2; 2]>::default();
+        colliders.iter().filter(|v| Self::is_obstacle(v)).for_each(|v| {
+            current.insert(v.identifier, Self::entry_bounds(v));
+        });
+
+        let mut dirty = self.entries.iter()
+            .filter(|(k, _)| !current.contains_key(*k))
+            .map(|(_, v)| *v)
+            .collect::<Vec<_>>();
+        dirty.extend(current.iter().filter(|(k, _)| !self.entries.contains_key(*k)).map(|(_, v)| *v));
+        self.entries = current;
+
+        for [min, max] in dirty {
+            let reach = Vec2::splat(self.radius);
+            let min = ((min - reach)/self.cell_size).floor().as_ivec2();
+            let max = ((max + reach)/self.cell_size).ceil().as_ivec2();
+            self.rebuild_region(colliders, min, max);
+        }
+        true
+    }
+
+    /// Rebuilds every cell over the map's populated chunks
+    pub fn rebuild(&mut self, colliders: &CollisionMap) {
+        self.revision   = Some(colliders.revision());
+        self.generation = colliders.generation();
+        self.version   += 1;
+        self.version_rebuilt = self.version;
+        self.changes.clear();
+        self.entries = colliders.iter()
+            .filter(|v| Self::is_obstacle(v))
+            .map(|v| (v.identifier, Self::entry_bounds(v)))
+            .collect();
+
+        let Some(bounds) = colliders.chunk_bounds() else {
+            self.size = UVec2::ZERO;
+            self.walkable.clear();
+            return;
+        };
+
+        let [min, max] = Self::chunk_bounds_to_world(bounds);
+        self.min  = self.cell_of(min);
+        self.size = (self.cell_of(max) - self.min + IVec2::ONE).as_uvec2();
+        self.walkable = vec![false; (self.size.x as usize)*(self.size.y as usize)];
+        self.rebuild_region(colliders, self.min, self.min + self.size.as_ivec2() - IVec2::ONE);
+
+        // Every cell was just tested, none of them changed relative to anything
+        self.changes.clear();
+    }
+
+    /// Re-tests the cells within the inclusive region, recording those that changed
+    pub fn rebuild_region(&mut self, colliders: &CollisionMap, min: IVec2, max: IVec2) {
+        let shape = ShapeMoving::from(Circle::new((self.radius - Self::CLEARANCE_TOLERANCE).max(0.0)));
+        for y in min.y..=max.y {
+            for x in min.x..=max.x {
+                let cell = IVec2::new(x, y);
+                let Some(i) = self.index(cell) else { continue; };
+                let walkable = self.is_clear(colliders, &shape, self.cell_center(cell));
+                if self.walkable[i] != walkable {
+                    self.walkable[i] = walkable;
+                    self.changes.push((cell, walkable));
+                }
+            }
+        }
+    }
+
+    /// Pawns, bodies and platforms move, only static geometry shapes the grid
+    const fn is_obstacle(entry: &CollisionMapEntry) -> bool {
+        !entry.sensor && !entry.dynamic
+    }
+
+    fn entry_bounds(entry: &CollisionMapEntry) -> [Vec2; 2] {
+        let [min, max] = entry.collider.bounding_box();
+        [entry.origin + min, entry.origin + max]
+    }
+
+    fn chunk_bounds_to_world([min, max]: [IVec2; 2]) -> [Vec2; 2] {
+        [
+            min.as_vec2()*CollisionMap::CHUNK_SIZE,
+            (max + IVec2::ONE).as_vec2()*CollisionMap::CHUNK_SIZE,
+        ]
+    }
+
+    fn is_clear(&self, colliders: &CollisionMap, shape: &ShapeMoving, point: Vec2) -> bool {
+        let [min, max] = shape.bounding_box();
+        let mut clear = true;
+
+        colliders.visit_filtered(point + min, point + max, self.filter, |entry| {
+            if clear && Self::is_obstacle(entry) {
+                clear = !ShapeCombined::between_moving_and_static(shape, &entry.collider).contains_point(entry.origin, point);
+            }
+        });
+        clear
+    }
+
+    fn index(&self, cell: IVec2) -> Option<usize> {
+        let local = cell - self.min;
+        (local.cmpge(IVec2::ZERO).all() && local.as_uvec2().cmplt(self.size).all())
+            .then(|| (local.y as usize)*(self.size.x as usize) + (local.x as usize))
+    }
+
+}
@@ -0,0 +1,164 @@
+// Copyright 2025 Natalie Baker // AGPLv3 //
+
+use bevy::prelude::*;
+
+use crate::{collision::CollisionMap, pawn::{Pawn, PawnMove}, platform::PlatformSystems};
+
+mod flow;
+pub use flow::*;
+
+mod grid;
+pub use grid::*;
+
+mod path;
+pub use path::*;
+
+#[cfg(test)]
+mod fixtures;
+
+pub struct PluginNav;
+
+impl Plugin for PluginNav {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<NavGrids>()
+            .add_systems(FixedUpdate, (nav_grid_sync, flow_field_update, pawn_path_follow, flow_field_follow).chain().after(PlatformSystems));
+    }
+}
+
+/// Waypoints a pawn walks through in order, issuing a [`PawnMove`] towards the current one each tick
+#[derive(Debug, Clone, Component)]
+pub struct PawnPath {
+    waypoints: Vec<Vec2>,
+    index:     usize,
+    speed:     f32,
+}
+
+impl PawnPath {
+
+    /// How close a pawn must get to a waypoint before moving on to the next
+    pub const ARRIVE_DISTANCE: f32 = 0.05;
+
+    /// Speed is in units per second
+    #[must_use]
+    pub const fn new(waypoints: Vec<Vec2>, speed: f32) -> Self {
+        Self { waypoints, index: 0, speed }
+    }
+
+    /// Path from the start to the goal through the grid, or None when the goal can't be reached.
+    /// Pick the grid by the pawn's size, see [`NavGrids::for_pawn`].
+    #[must_use]
+    pub fn find(grid: &NavGrid, colliders: &CollisionMap, start: Vec2, goal: Vec2, speed: f32) -> Option<Self> {
+        grid.find_path(colliders, start, goal).map(|v| Self::new(v, speed))
+    }
+
+    #[must_use]
+    pub fn waypoints(&self) -> &[Vec2] {
+        &self.waypoints
+    }
+
+    /// The waypoint currently being walked towards
+    #[must_use]
+    pub fn current(&self) -> Option<Vec2> {
+        self.waypoints.get(self.index).copied()
+    }
+
+    #[must_use]
+    pub fn goal(&self) -> Option<Vec2> {
+        self.waypoints.last().copied()
+    }
+
+    #[must_use]
+    pub fn finished(&self) -> bool {
+        self.index >= self.waypoints.len()
+    }
+
+    #[must_use]
+    pub const fn speed(&self) -> f32 {
+        self.speed
+    }
+
+    pub const fn set_speed(&mut self, v: f32) {
+        self.speed = v;
+    }
+
+    /// The move for this tick, skipping any waypoints the pawn has reached
+    pub fn next_move(&mut self, entity: Entity, origin: Vec2, delta: f32) -> Option<PawnMove> {
+        while self.current().is_some_and(|v| v.distance(origin) <= Self::ARRIVE_DISTANCE) {
+            self.index += 1;
+        }
+        self.current().map(|v| PawnMove::absolute(entity, v).with_speed(self.speed*delta).with_steering())
+    }
+
+}
+
+/// Follows the [`FlowField`] on another entity, stepping from cell to cell until reaching a goal
+#[derive(Debug, Clone, Copy, Component)]
+pub struct FlowFollower {
+    pub field: Entity,
+    /// Units per second
+    pub speed: f32,
+}
+
+/// Patches the grids whenever static colliders change, see [`NavGrid::update`]
+pub fn nav_grid_sync(
+    mut r_nav_grids: ResMut<NavGrids>,
+    r_collision_map: Res<CollisionMap>,
+) {
+    if r_nav_grids.iter().any(|v| v.revision() != Some(r_collision_map.revision())) {
+        r_nav_grids.update(&r_collision_map);
+    }
+}
+
+pub fn pawn_path_follow(
+    mut commands: Commands,
+    mut q_pawns: Query<(Entity, &Pawn, &mut PawnPath)>,
+    r_time: Res<Time>,
+) {
+    let delta = r_time.delta_secs();
+    for (entity, pawn, mut path) in &mut q_pawns {
+        if let Some(action) = path.next_move(entity, pawn.origin(), delta) {
+            action.do_deferred(&mut commands);
+        } else {
+            commands.entity(entity).remove::<PawnPath>();
+        }
+    }
+}
+
+/// Keeps fields on pawns targeting that pawn, then brings every field up to date with the grid
+pub fn flow_field_update(
+    mut q_fields: Query<(&mut FlowField, Option<&Pawn>)>,
+    r_nav_grids: Res<NavGrids>,
+) {
+    for (mut field, pawn) in &mut q_fields {
+        let grid = r_nav_grids.for_radius(field.radius());
+        if let Some(pawn) = pawn {
+            let goal = grid.cell_of(pawn.origin());
+            if field.goals() != [goal] {
+                field.set_goals([goal]);
+            }
+        }
+
+        field.update(grid);
+    }
+}
+
+pub fn flow_field_follow(
+    mut commands: Commands,
+    q_followers: Query<(Entity, &Pawn, &FlowFollower)>,
+    q_fields: Query<&FlowField>,
+    r_nav_grids: Res<NavGrids>,
+    r_time: Res<Time>,
+) {
+    let delta = r_time.delta_secs();
+    for (entity, pawn, follower) in &q_followers {
+        let Ok(field) = q_fields.get(follower.field) else { continue; };
+        let grid = r_nav_grids.for_radius(field.radius());
+
+        // Head for the center of the next cell, which keeps pawns off the corners diagonals skirt
+        let cell = grid.cell_of(pawn.origin());
+        if let Some(next) = field.next_cell(cell) {
+            PawnMove::absolute(entity, grid.cell_center(next)).with_speed(follower.speed*delta).with_steering().do_deferred(&mut commands);
+        }
+    }
+}
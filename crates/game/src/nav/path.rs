@@ -0,0 +1,202 @@
+// Copyright 2025 Natalie Baker // AGPLv3 //
+
+use core::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+use bevy::{math::{IVec2, Vec2}, platform_support::collections::hash_map::HashMap, prelude::Circle};
+
+use crate::collision::{CollisionFilter, CollisionMap};
+
+use super::NavGrid;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) struct OpenCell {
+    pub cost: f32,
+    pub cell: IVec2,
+}
+
+impl Eq for OpenCell {}
+
+impl Ord for OpenCell {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Reversed, so the heap pops the cheapest cell first
+        other.cost.total_cmp(&self.cost)
+    }
+}
+
+impl PartialOrd for OpenCell {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Octile distance, exact for 8-connected grids without obstacles
+fn heuristic(a: IVec2, b: IVec2) -> f32 {
+    let delta = (a - b).abs();
+    let (long, short) = (delta.max_element() as f32, delta.min_element() as f32);
+    long + (core::f32::consts::SQRT_2 - 1.0)*short
+}
+
+impl NavGrid {
+
+    /// Cells from the start to the goal, inclusive, by A*. The start may be blocked, ie. a pawn
+    /// pressed against a wall, but the goal must be walkable.
+    #[must_use]
+    pub fn find_cells(&self, start: IVec2, goal: IVec2) -> Option<Vec<IVec2>> {
+        if !self.contains(start) || !self.is_walkable(goal) {
+            return None;
+        }
+
+        let mut open   = BinaryHeap::from([OpenCell{cost: heuristic(start, goal), cell: start}]);
+        let mut costs  = HashMap::<IVec2, f32>::from_iter([(start, 0.0)]);
+        let mut parent = HashMap::<IVec2, IVec2>::default();
+
+        while let Some(OpenCell{cell, cost}) = open.pop() {
+            if cell == goal {
+                let mut result = vec![goal];
+                while let Some(&prev) = parent.get(result.last().unwrap()) {
+                    result.push(prev);
+                }
+                result.reverse();
+                return Some(result);
+            }
+
+            // Stale entry, the cell was reached more cheaply since it was pushed
+            let cost_here = costs[&cell];
+            if cost > cost_here + heuristic(cell, goal) {
+                continue;
+            }
+
+            for (next, step) in self.neighbours(cell) {
+                let cost_next = cost_here + step;
+                if costs.get(&next).is_none_or(|&v| cost_next < v) {
+                    costs.insert(next, cost_next);
+                    parent.insert(next, cell);
+                    open.push(OpenCell{cost: cost_next + heuristic(next, goal), cell: next});
+                }
+            }
+        }
+
+        None
+    }
+
+    /// World-space waypoints from the start to the goal, smoothed by [`smooth_path`]
+    #[must_use]
+    pub fn find_path(&self, colliders: &CollisionMap, start: Vec2, goal: Vec2) -> Option<Vec<Vec2>> {
+        let cells = self.find_cells(self.cell_of(start), self.cell_of(goal))?;
+
+        // Swap the cell centers at either end for the exact points
+        let mut points = cells.into_iter().map(|v| self.cell_center(v)).collect::<Vec<_>>();
+        points[0] = start;
+        *points.last_mut().unwrap() = goal;
+
+        Some(smooth_path(colliders, self.radius(), self.filter(), &points))
+    }
+
+}
+
+/// True when a pawn of the radius could move straight from `a` to `b` without touching static colliders
+#[must_use]
+pub fn has_clearance(colliders: &CollisionMap, radius: f32, filter: CollisionFilter, a: Vec2, b: Vec2) -> bool {
+    let offset   = b - a;
+    let distance = offset.length();
+    if distance <= 0.0 {
+        return true;
+    }
+
+    let shape = Circle::new((radius - NavGrid::CLEARANCE_TOLERANCE).max(0.0));
+    colliders.shape_cast_all(shape, a, offset/distance, distance, filter)
+        .iter()
+        .all(|v| v.entry.sensor || v.entry.dynamic)
+}
+
+/// Drops waypoints the pawn can skip, by walking to the furthest waypoint in sight of each one
+#[must_use]
+pub fn smooth_path(colliders: &CollisionMap, radius: f32, filter: CollisionFilter, points: &[Vec2]) -> Vec<Vec2> {
+    let Some(&first) = points.first() else { return Vec::new(); };
+
+    let mut result = vec![first];
+    let mut from = 0;
+    while from + 1 < points.len() {
+        let next = (from + 2..points.len()).rev()
+            .find(|&i| has_clearance(colliders, radius, filter, points[from], points[i]))
+            .unwrap_or(from + 1);
+        result.push(points[next]);
+        from = next;
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::math::Vec2;
+
+    use crate::{collision::{CollisionFilter, CollisionMap}, nav::fixtures::split_room};
+
+    use super::{has_clearance, smooth_path, NavGrid};
+
+    fn grid(map: &CollisionMap, radius: f32) -> NavGrid {
+        let mut grid = NavGrid::new(0.5, radius, CollisionFilter::DEFAULT);
+        grid.rebuild(map);
+        grid
+    }
+
+    fn length(points: &[Vec2]) -> f32 {
+        points.windows(2).map(|v| v[0].distance(v[1])).sum()
+    }
+
+    #[test]
+    fn finds_cells_through_the_gap() {
+        let map  = split_room();
+        let grid = grid(&map, 0.25);
+
+        let start = grid.cell_of(Vec2::new(2.0, 2.0));
+        let goal  = grid.cell_of(Vec2::new(7.0, 2.0));
+        let cells = grid.find_cells(start, goal).expect("the gap fits the radius");
+
+        assert_eq!(cells.first(), Some(&start));
+        assert_eq!(cells.last(),  Some(&goal));
+        assert!(cells.iter().all(|&v| grid.is_walkable(v)));
+        assert!(cells.windows(2).all(|v| (v[1] - v[0]).abs().max_element() == 1));
+
+        let through_wall = cells.iter().map(|&v| grid.cell_center(v)).filter(|v| (4.0..=5.0).contains(&v.x));
+        assert!(through_wall.into_iter().all(|v| (4.0..=5.0).contains(&v.y)));
+    }
+
+    #[test]
+    fn finds_nothing_when_the_gap_is_too_narrow() {
+        let map  = split_room();
+        let grid = grid(&map, 0.6);
+
+        let start = grid.cell_of(Vec2::new(2.0, 2.0));
+        let goal  = grid.cell_of(Vec2::new(7.0, 2.0));
+        assert!(grid.is_walkable(start) && grid.is_walkable(goal));
+        assert!(grid.find_cells(start, goal).is_none());
+    }
+
+    #[test]
+    fn smooths_an_open_line_to_its_ends() {
+        let map    = split_room();
+        let points = (2..=12).map(|v| Vec2::new(v as f32*0.25, 2.0)).collect::<Vec<_>>();
+        let result = smooth_path(&map, 0.25, CollisionFilter::DEFAULT, &points);
+        assert_eq!(result, [points[0], points[points.len() - 1]]);
+    }
+
+    #[test]
+    fn smoothed_paths_are_shorter_and_clear() {
+        let map  = split_room();
+        let grid = grid(&map, 0.25);
+
+        let start = Vec2::new(2.0, 2.0);
+        let goal  = Vec2::new(7.0, 2.0);
+        let cells = grid.find_cells(grid.cell_of(start), grid.cell_of(goal)).unwrap();
+        let raw   = cells.into_iter().map(|v| grid.cell_center(v)).collect::<Vec<_>>();
+        let path  = grid.find_path(&map, start, goal).unwrap();
+
+        assert_eq!(path.first(), Some(&start));
+        assert_eq!(path.last(),  Some(&goal));
+        assert!(path.len() < raw.len());
+        assert!(length(&path) < length(&raw));
+        assert!(path.windows(2).all(|v| has_clearance(&map, grid.radius(), grid.filter(), v[0], v[1])));
+    }
+}
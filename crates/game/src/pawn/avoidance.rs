@@ -0,0 +1,237 @@
+// Copyright 2025 Natalie Baker // AGPLv3 //
+
+use core::f32::consts::PI;
+
+use bevy::prelude::*;
+
+/// Steers a pawn's moves around neighbouring pawns, by picking the velocity closest to the
+/// desired one that avoids colliding with them within the horizon. Only moves made
+/// [`super::PawnMove::with_steering`] are adjusted, and the result is still solved against
+/// static geometry afterwards.
+///
+/// Avoidance is shared like reciprocal velocity obstacles, split by priority. A pawn yields
+/// more to those with a higher priority, and fully to pawns without avoidance, such as the player.
+/// Neighbours are expected to keep their [`super::Pawn::velocity`] from the last tick.
+#[derive(Debug, Clone, Copy, Component)]
+pub struct PawnAvoidance {
+    radius:   f32,
+    horizon:  f32,
+    priority: f32,
+}
+
+/// A pawn to steer around, relative to the steering pawn
+#[derive(Debug, Clone, Copy)]
+pub struct AvoidanceNeighbour {
+    pub offset:   Vec2,
+    pub velocity: Vec2,
+    pub radius:   f32,
+    /// How much of the avoidance falls to the steering pawn, from 0 to 1
+    pub responsibility: f32,
+}
+
+impl Default for PawnAvoidance {
+    fn default() -> Self {
+        Self::new(0.5)
+    }
+}
+
+impl PawnAvoidance {
+
+    pub const DEFAULT_HORIZON:  f32 = 1.0;
+    pub const DEFAULT_PRIORITY: f32 = 1.0;
+
+    /// How much a collision at the current time outweighs straying from the desired velocity
+    const THREAT_WEIGHT: f32 = 2.0;
+
+    /// Directions tried relative to the desired velocity, each at full and half speed
+    const SAMPLE_ANGLES: [f32; 11] = [0.0, PI/8.0, -PI/8.0, PI/4.0, -PI/4.0, 3.0*PI/8.0, -3.0*PI/8.0, PI/2.0, -PI/2.0, 3.0*PI/4.0, -3.0*PI/4.0];
+    const SAMPLE_SCALES: [f32; 2]  = [1.0, 0.5];
+
+    /// The radius is the personal space kept from other pawns, usually the pawn's own radius
+    #[must_use]
+    pub const fn new(radius: f32) -> Self {
+        Self {
+            radius,
+            horizon: Self::DEFAULT_HORIZON,
+            priority: Self::DEFAULT_PRIORITY,
+        }
+    }
+
+    /// Seconds ahead that collisions are avoided
+    #[must_use]
+    pub const fn with_horizon(mut self, horizon: f32) -> Self {
+        self.horizon = horizon;
+        self
+    }
+
+    #[must_use]
+    pub const fn with_priority(mut self, priority: f32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    #[must_use]
+    pub const fn radius(&self) -> f32 {
+        self.radius
+    }
+
+    pub const fn set_radius(&mut self, v: f32) {
+        self.radius = v;
+    }
+
+    #[must_use]
+    pub const fn horizon(&self) -> f32 {
+        self.horizon
+    }
+
+    pub const fn set_horizon(&mut self, v: f32) {
+        self.horizon = v;
+    }
+
+    #[must_use]
+    pub const fn priority(&self) -> f32 {
+        self.priority
+    }
+
+    pub const fn set_priority(&mut self, v: f32) {
+        self.priority = v;
+    }
+
+    /// How much of the avoidance between this and a pawn with the given priority falls to this one
+    #[must_use]
+    pub fn responsibility(&self, other_priority: Option<f32>) -> f32 {
+        other_priority.map_or(1.0, |v| {
+            let total = self.priority + v;
+            if total > 0.0 { v/total } else { 0.5 }
+        })
+    }
+
+    /// How far around the pawn neighbours could collide with it within the horizon, moving at up to `speed`
+    #[must_use]
+    pub fn reach(&self, speed: f32) -> f32 {
+        2.0*(self.radius + self.horizon*speed)
+    }
+
+    /// The velocity closest to the desired one that best avoids the neighbours, from the pawn's
+    /// current velocity, see [`super::Pawn::velocity`]
+    #[must_use]
+    pub fn steer(&self, current: Vec2, desired: Vec2, neighbours: &[AvoidanceNeighbour]) -> Vec2 {
+        let speed = desired.length();
+        if speed <= 0.0 || neighbours.is_empty() {
+            return desired;
+        }
+
+        let candidates = Self::SAMPLE_SCALES.iter()
+            .flat_map(|&scale| Self::SAMPLE_ANGLES.iter().map(move |&angle| Vec2::from_angle(angle).rotate(desired)*scale))
+            .chain([Vec2::ZERO]);
+
+        candidates
+            .map(|v| (v, self.penalty(v, current, desired, speed, neighbours)))
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map_or(desired, |v| v.0)
+    }
+
+    fn penalty(&self, velocity: Vec2, current: Vec2, desired: Vec2, speed: f32, neighbours: &[AvoidanceNeighbour]) -> f32 {
+        let threat = neighbours.iter().map(|other| {
+            // The neighbour's share of the avoidance is assumed taken, so test against the split
+            let apex     = current + (other.velocity - current)*other.responsibility;
+            let relative = velocity - apex;
+            let radius   = self.radius + other.radius;
+            self.time_to_collision(other.offset, relative, radius).map_or(0.0, |t| 1.0 - t/self.horizon)
+        }).fold(0.0, f32::max);
+
+        (velocity - desired).length()/speed + Self::THREAT_WEIGHT*threat
+    }
+
+    /// Seconds until a circle of the radius at the offset is hit moving at the velocity, if within the horizon
+    fn time_to_collision(&self, offset: Vec2, velocity: Vec2, radius: f32) -> Option<f32> {
+        let c = offset.length_squared() - radius*radius;
+        if c <= 0.0 {
+            // Already overlapping, only moving apart is safe
+            return (velocity.dot(offset) > 0.0).then_some(0.0);
+        }
+
+        let a = velocity.length_squared();
+        let b = velocity.dot(offset);
+        let discriminant = b*b - a*c;
+        if a <= 0.0 || b <= 0.0 || discriminant < 0.0 {
+            return None;
+        }
+
+        let t = (b - discriminant.sqrt())/a;
+        (t <= self.horizon).then_some(t)
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::math::Vec2;
+
+    use super::{AvoidanceNeighbour, PawnAvoidance};
+
+    fn neighbour(offset: Vec2, velocity: Vec2, responsibility: f32) -> AvoidanceNeighbour {
+        AvoidanceNeighbour{offset, velocity, radius: 0.5, responsibility}
+    }
+
+    /// Whether the steered velocity still hits the neighbour within the horizon, given its share
+    fn collides(avoidance: &PawnAvoidance, current: Vec2, steered: Vec2, other: &AvoidanceNeighbour) -> bool {
+        let apex = current + (other.velocity - current)*other.responsibility;
+        avoidance.time_to_collision(other.offset, steered - apex, avoidance.radius() + other.radius).is_some()
+    }
+
+    #[test]
+    fn head_on_pairs_veer_to_opposite_sides() {
+        let avoidance = PawnAvoidance::new(0.5);
+        let split = avoidance.responsibility(Some(PawnAvoidance::DEFAULT_PRIORITY));
+
+        let a = neighbour(Vec2::new(-1.5, 0.0), Vec2::new( 2.0, 0.0), split);
+        let b = neighbour(Vec2::new( 1.5, 0.0), Vec2::new(-2.0, 0.0), split);
+        let steer_a = avoidance.steer(a.velocity, a.velocity, &[b]);
+        let steer_b = avoidance.steer(b.velocity, b.velocity, &[a]);
+
+        assert!(collides(&avoidance, a.velocity, a.velocity, &b), "the desired velocities should collide");
+        assert!(!collides(&avoidance, a.velocity, steer_a, &b));
+        assert!(!collides(&avoidance, b.velocity, steer_b, &a));
+
+        // Each keeps to its own left, so they pass rather than mirror each other
+        assert!(steer_a.y > 0.0 && steer_b.y < 0.0);
+        assert!(steer_a.x > 0.0 && steer_b.x < 0.0);
+    }
+
+    #[test]
+    fn crossing_pairs_turn_less_than_head_on() {
+        let avoidance = PawnAvoidance::new(0.5);
+        let split = avoidance.responsibility(Some(PawnAvoidance::DEFAULT_PRIORITY));
+
+        let desired  = Vec2::new(2.0, 0.0);
+        let crossing = neighbour(Vec2::new(1.5, -1.5), Vec2::new(0.0, 2.0), split);
+        let steered  = avoidance.steer(desired, desired, &[crossing]);
+
+        assert!(collides(&avoidance, desired, desired, &crossing), "the desired velocity should collide");
+        assert!(!collides(&avoidance, desired, steered, &crossing));
+        assert!(steered != desired);
+        assert!(desired.angle_to(steered).abs() < core::f32::consts::FRAC_PI_4);
+    }
+
+    #[test]
+    fn priority_splits_who_yields() {
+        let avoidance = PawnAvoidance::new(0.5);
+        let desired   = Vec2::new(2.0, 0.0);
+        let steer_with = |responsibility: f32| {
+            avoidance.steer(desired, desired, &[neighbour(Vec2::new(1.5, 0.0), Vec2::new(-2.0, 0.0), responsibility)])
+        };
+
+        assert!((avoidance.responsibility(Some(3.0)) - 0.75).abs() < 1e-6);
+        assert!((avoidance.responsibility(None) - 1.0).abs() < 1e-6);
+
+        // A neighbour with no priority does all the yielding, one without avoidance none of it
+        let ignoring = steer_with(avoidance.responsibility(Some(0.0)));
+        let sharing  = steer_with(avoidance.responsibility(Some(PawnAvoidance::DEFAULT_PRIORITY)));
+        let yielding = steer_with(avoidance.responsibility(None));
+
+        assert_eq!(ignoring, desired);
+        assert!(desired.angle_to(sharing).abs() > 0.1);
+        assert!(desired.angle_to(yielding).abs() > desired.angle_to(sharing).abs());
+    }
+}
@@ -0,0 +1,94 @@
+// Copyright 2025 Natalie Baker // AGPLv3 //
+
+use bevy::prelude::*;
+
+use crate::collision::CollisionHit;
+
+/// Contacts from the pawn's most recent move
+#[derive(Debug, Clone, Component)]
+pub struct PawnContacts {
+    ground_threshold: f32,
+    grounded:   bool,
+    wall_left:  bool,
+    wall_right: bool,
+    ceiling:    bool,
+    hits:       Vec<CollisionHit>,
+}
+
+impl PawnContacts {
+
+    pub const DEFAULT_GROUND_THRESHOLD: f32 = 0.7;
+
+    #[must_use]
+    pub const fn new(ground_threshold: f32) -> Self {
+        Self {
+            ground_threshold,
+            grounded:   false,
+            wall_left:  false,
+            wall_right: false,
+            ceiling:    false,
+            hits:       Vec::new(),
+        }
+    }
+
+    /// Minimum normal y-component of a surface to count as ground, the cosine of the steepest walkable slope
+    #[must_use]
+    pub const fn ground_threshold(&self) -> f32 {
+        self.ground_threshold
+    }
+
+    pub const fn set_ground_threshold(&mut self, v: f32) {
+        self.ground_threshold = v;
+    }
+
+    pub fn update(&mut self, hits: &[CollisionHit]) {
+        let threshold = self.ground_threshold;
+        self.grounded   = hits.iter().any(|v| v.hit.normal.y >=  threshold);
+        self.ceiling    = hits.iter().any(|v| v.hit.normal.y <= -threshold);
+        self.wall_left  = hits.iter().any(|v| v.hit.normal.x >=  threshold);
+        self.wall_right = hits.iter().any(|v| v.hit.normal.x <= -threshold);
+        self.hits.clear();
+        self.hits.extend_from_slice(hits);
+    }
+
+    #[must_use]
+    pub const fn grounded(&self) -> bool {
+        self.grounded
+    }
+
+    #[must_use]
+    pub const fn ceiling(&self) -> bool {
+        self.ceiling
+    }
+
+    #[must_use]
+    pub const fn wall_left(&self) -> bool {
+        self.wall_left
+    }
+
+    #[must_use]
+    pub const fn wall_right(&self) -> bool {
+        self.wall_right
+    }
+
+    #[must_use]
+    pub fn hits(&self) -> &[CollisionHit] {
+        &self.hits
+    }
+
+    pub fn normals(&self) -> impl Iterator<Item = Vec2> + '_ {
+        self.hits.iter().map(|v| v.hit.normal)
+    }
+
+    #[must_use]
+    pub fn last_normal(&self) -> Option<Vec2> {
+        self.hits.last().map(|v| v.hit.normal)
+    }
+
+}
+
+impl Default for PawnContacts {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_GROUND_THRESHOLD)
+    }
+}
@@ -0,0 +1,192 @@
+// Copyright 2025 Natalie Baker // AGPLv3 //
+
+use bevy::prelude::*;
+
+use super::{PawnContacts, PawnMove, PawnPlatformer};
+
+/// Gives a pawn momentum, turning an input direction into changes in velocity that are moved
+/// through each tick, see [`pawn_controller_move`].
+///
+/// Velocity into the surfaces the pawn touched on its last move is removed, so pressing into a
+/// wall doesn't build up speed that's released the moment it ends.
+#[derive(Debug, Clone, Copy, Component)]
+pub struct PawnController {
+    input:           Vec2,
+    velocity:        Vec2,
+    acceleration:    f32,
+    deceleration:    f32,
+    max_speed:       f32,
+    friction_ground: f32,
+    friction_air:    f32,
+    grounded:        bool,
+}
+
+impl Default for PawnController {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_MAX_SPEED)
+    }
+}
+
+impl PawnController {
+
+    pub const DEFAULT_MAX_SPEED:       f32 = 4.0;
+    pub const DEFAULT_ACCELERATION:    f32 = 40.0;
+    pub const DEFAULT_DECELERATION:    f32 = 60.0;
+    pub const DEFAULT_FRICTION_GROUND: f32 = 0.0;
+    pub const DEFAULT_FRICTION_AIR:    f32 = 0.0;
+
+    /// Max speed is in units per second
+    #[must_use]
+    pub const fn new(max_speed: f32) -> Self {
+        Self {
+            input:           Vec2::ZERO,
+            velocity:        Vec2::ZERO,
+            acceleration:    Self::DEFAULT_ACCELERATION,
+            deceleration:    Self::DEFAULT_DECELERATION,
+            max_speed,
+            friction_ground: Self::DEFAULT_FRICTION_GROUND,
+            friction_air:    Self::DEFAULT_FRICTION_AIR,
+            grounded:        true,
+        }
+    }
+
+    /// Units per second squared, towards the input velocity while there's input
+    #[must_use]
+    pub const fn with_acceleration(mut self, acceleration: f32) -> Self {
+        self.acceleration = acceleration;
+        self
+    }
+
+    /// Units per second squared, towards rest while there's no input
+    #[must_use]
+    pub const fn with_deceleration(mut self, deceleration: f32) -> Self {
+        self.deceleration = deceleration;
+        self
+    }
+
+    /// Exponential drag per second, on top of acceleration and deceleration, for while grounded and airborne
+    #[must_use]
+    pub const fn with_friction(mut self, ground: f32, air: f32) -> Self {
+        self.friction_ground = ground;
+        self.friction_air    = air;
+        self
+    }
+
+    /// The direction to move in, scaled by how much of the max speed to move at. Clamped to a length of 1.
+    #[must_use]
+    pub const fn input(&self) -> Vec2 {
+        self.input
+    }
+
+    pub fn set_input(&mut self, v: Vec2) {
+        self.input = v.clamp_length_max(1.0);
+    }
+
+    /// Units per second
+    #[must_use]
+    pub const fn velocity(&self) -> Vec2 {
+        self.velocity
+    }
+
+    pub const fn set_velocity(&mut self, v: Vec2) {
+        self.velocity = v;
+    }
+
+    #[must_use]
+    pub const fn acceleration(&self) -> f32 {
+        self.acceleration
+    }
+
+    pub const fn set_acceleration(&mut self, v: f32) {
+        self.acceleration = v;
+    }
+
+    #[must_use]
+    pub const fn deceleration(&self) -> f32 {
+        self.deceleration
+    }
+
+    pub const fn set_deceleration(&mut self, v: f32) {
+        self.deceleration = v;
+    }
+
+    #[must_use]
+    pub const fn max_speed(&self) -> f32 {
+        self.max_speed
+    }
+
+    pub const fn set_max_speed(&mut self, v: f32) {
+        self.max_speed = v;
+    }
+
+    #[must_use]
+    pub const fn friction_ground(&self) -> f32 {
+        self.friction_ground
+    }
+
+    #[must_use]
+    pub const fn friction_air(&self) -> f32 {
+        self.friction_air
+    }
+
+    /// Selects between ground and air friction. Always true unless something, ie. a platformer
+    /// controller, says otherwise.
+    #[must_use]
+    pub const fn grounded(&self) -> bool {
+        self.grounded
+    }
+
+    pub const fn set_grounded(&mut self, v: bool) {
+        self.grounded = v;
+    }
+
+    /// Steps the velocity towards the input, then applies friction
+    pub fn accelerate(&mut self, delta: f32) {
+        self.accelerate_axes(delta, BVec2::TRUE);
+    }
+
+    /// As [`PawnController::accelerate`], leaving the velocity on the other axes alone, ie. for gravity
+    pub fn accelerate_axes(&mut self, delta: f32, axes: BVec2) {
+        let mask   = Vec2::select(axes, Vec2::ONE, Vec2::ZERO);
+        let input  = self.input*mask;
+        let target = input*self.max_speed;
+        let rate   = if input == Vec2::ZERO { self.deceleration } else { self.acceleration };
+
+        let change = (target - self.velocity)*mask;
+        self.velocity += change.clamp_length_max(rate*delta);
+
+        let friction = if self.grounded { self.friction_ground } else { self.friction_air };
+        self.velocity *= Vec2::select(axes, Vec2::splat((-friction*delta).exp()), Vec2::ONE);
+    }
+
+    /// Removes the velocity heading into each of the normals
+    pub fn remove_blocked(&mut self, normals: impl IntoIterator<Item = Vec2>) {
+        for normal in normals {
+            let into = self.velocity.dot(normal);
+            if into < 0.0 {
+                self.velocity -= normal*into;
+            }
+        }
+    }
+
+}
+
+/// Accelerates controlled pawns and moves them by their velocity, dropping what their last move was blocked by first.
+/// Platformer pawns are moved by [`super::pawn_platformer_move`] instead.
+pub fn pawn_controller_move(
+    mut commands: Commands,
+    mut q_pawns: Query<(Entity, &mut PawnController, Option<&PawnContacts>), Without<PawnPlatformer>>,
+    r_time: Res<Time>,
+) {
+    let delta = r_time.delta_secs();
+    for (entity, mut controller, contacts) in &mut q_pawns {
+        if let Some(contacts) = contacts {
+            controller.remove_blocked(contacts.normals());
+        }
+
+        controller.accelerate(delta);
+        if controller.velocity() != Vec2::ZERO {
+            PawnMove::relative(entity, controller.velocity()*delta).do_deferred(&mut commands);
+        }
+    }
+}
@@ -1,37 +1,200 @@
 // Copyright 2025 Natalie Baker // AGPLv3 //
 
 use bevy::prelude::*;
+use bevy_asset_ldtk::accessors::LdtkEntity;
+use raydee::prelude::{debug_draw_queue_render, DebugDrawLifetime, DebugDrawOptions, DebugDrawQueue, PluginDebugDraw, ShapeCombined, ShapeCommon, ShapeMoving};
+
+use crate::{collision::{CollisionFilter, CollisionMap}, scale::{ppu_snap_to, PixelsPerUnit}};
+
+mod avoidance;
+pub use avoidance::*;
+
+mod contacts;
+pub use contacts::*;
+
+mod controller;
+pub use controller::*;
 
 mod movement;
 pub use movement::*;
 
+mod platformer;
+pub use platformer::*;
+
+mod sensor;
+pub use sensor::*;
+
+/// Pawn movement is simulated in [`FixedUpdate`], so collisions don't depend on frame rate,
+/// and rendered between the last two ticks, see [`sync_pawn_transform`]
+pub struct PluginPawn {
+    /// Simulation ticks per second
+    pub tick_rate: f64,
+    pub transform: PawnTransformSettings,
+    /// Outlines every pawn's collider, see [`pawn_debug_draw`]
+    pub debug_draw: bool,
+}
+
+impl Default for PluginPawn {
+    fn default() -> Self {
+        Self {
+            tick_rate: Self::DEFAULT_TICK_RATE,
+            transform: PawnTransformSettings::default(),
+            debug_draw: false,
+        }
+    }
+}
+
+impl PluginPawn {
+    pub const DEFAULT_TICK_RATE: f64 = 60.0;
+}
+
+impl Plugin for PluginPawn {
+    fn build(&self, app: &mut App) {
+        app
+            .insert_resource(Time::<Fixed>::from_hz(self.tick_rate))
+            .insert_resource(self.transform)
+            .add_systems(FixedFirst, pawn_store_previous_origin)
+            .add_systems(FixedUpdate, (pawn_controller_move, pawn_platformer_move).in_set(PawnControlSystems))
+            // After every deferred move and carry of the tick has been applied
+            .add_systems(FixedPostUpdate, pawn_sensors_update_idle)
+            .add_systems(PostUpdate, (pawn_collider_sync, sync_pawn_transform));
+
+        if self.debug_draw {
+            if !app.is_plugin_added::<PluginDebugDraw>() {
+                app.add_plugins(PluginDebugDraw);
+            }
+            app.add_systems(PostUpdate, pawn_debug_draw.before(debug_draw_queue_render));
+        }
+    }
+}
+
+/// Systems moving pawns by their controllers, anything setting controller input should run before
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, SystemSet)]
+pub struct PawnControlSystems;
+
+/// How pawn transforms follow their simulated origins, along with the platforms and bodies around them
+#[derive(Debug, Clone, Copy, Resource)]
+pub struct PawnTransformSettings {
+    /// Blends between the previous and current tick's origins, rather than snapping to the current one
+    pub interpolate: bool,
+    /// Rounds transforms to the nearest pixel of [`PixelsPerUnit`], when present
+    pub snap_to_pixels: bool,
+}
+
+impl Default for PawnTransformSettings {
+    fn default() -> Self {
+        Self { interpolate: true, snap_to_pixels: false }
+    }
+}
+
+impl PawnTransformSettings {
+
+    /// Where to render something that moved from `previous` to `current` over the last tick, with
+    /// `alpha` from [`Time::<Fixed>::overstep_fraction`]. Shared with the platforms and bodies pawns
+    /// stand on and push, so they all keep in step.
+    #[must_use]
+    pub fn render_origin(&self, previous: Vec2, current: Vec2, alpha: f32, ppu: Option<PixelsPerUnit>) -> Vec2 {
+        let origin = if self.interpolate { previous.lerp(current, alpha) } else { current };
+        ppu.filter(|_| self.snap_to_pixels).map_or(origin, |ppu| origin.map(|v| ppu_snap_to(ppu, v)))
+    }
+
+}
+
 #[derive(Debug, Clone, Component)]
 pub struct Pawn {
-    collider: Circle,
+    collider: ShapeMoving,
     origin: Vec2,
-    layers: u16,
+    origin_previous: Vec2,
+    velocity: Vec2,
+    filter: CollisionFilter,
+    mass: f32,
 }
 
 impl Pawn {
 
+    pub const DEFAULT_MASS: f32 = 1.0;
+
+    /// How far a collider may sink into others and still fit, so shapes resting against surfaces can change
+    pub const FIT_TOLERANCE: f32 = 1e-3;
+
     #[must_use]
     pub fn new(
         origin: Vec2, 
-        size: f32,
-        layers: u16
+        collider: impl Into<ShapeMoving>,
+        filter: CollisionFilter,
     ) -> Self {
         Self { 
-            collider: Circle::new(size/2.0),
+            collider: collider.into(),
             origin,
-            layers
+            origin_previous: origin,
+            velocity: Vec2::ZERO,
+            filter,
+            mass: Self::DEFAULT_MASS,
         }
     }   
 
+    /// Pawns push lighter pawns out of their way, and are blocked by heavier or equal ones
     #[must_use]
-    pub const fn collider(&self) -> Circle {
+    pub const fn with_mass(mut self, mass: f32) -> Self {
+        self.mass = mass;
+        self
+    }
+
+    #[must_use]
+    pub const fn collider(&self) -> ShapeMoving {
         self.collider
     }
 
+    /// Replaces the collider without checking it fits, see [`Pawn::try_set_collider`]
+    pub fn set_collider(&mut self, v: impl Into<ShapeMoving>) {
+        self.collider = v.into();
+    }
+
+    /// Replaces the collider and origin together, ie. keeping the feet planted while crouching,
+    /// unless the new collider would overlap anything solid there. Returns whether it was changed.
+    ///
+    /// The previous origin shifts along with it, so the change doesn't render as a slide, and the
+    /// new collider is registered right away for the moves after it.
+    pub fn try_set_collider(&mut self, colliders: &mut CollisionMap, entity: Entity, collider: impl Into<ShapeMoving>, origin: Vec2) -> bool {
+        let collider = collider.into();
+        if !Self::fits(colliders, entity, collider, origin, self.filter) {
+            return false;
+        }
+
+        self.origin_previous += origin - self.origin;
+        self.collider = collider;
+        self.origin   = origin;
+        register_pawn_collider(colliders, entity, self);
+        true
+    }
+
+    /// Whether the collider could stand at the origin without overlapping solid entries, other than the entity's own
+    #[must_use]
+    pub fn fits(colliders: &CollisionMap, entity: Entity, collider: ShapeMoving, origin: Vec2, filter: CollisionFilter) -> bool {
+        let shape = collider.shrunk_by(Self::FIT_TOLERANCE);
+        let [min, max] = shape.bounding_box();
+
+        let mut clear = true;
+        colliders.visit_filtered(origin + min, origin + max, filter, |entry| {
+            if clear && !entry.sensor && entry.entity != Some(entity) {
+                clear = !ShapeCombined::between_moving_and_static(&shape, &entry.collider).contains_point(entry.origin, origin);
+            }
+        });
+        clear
+    }
+
+    /// The collider from an LDtk entity's size, as a circle fitting within it unless the shape
+    /// field is set to "Rectangle"
+    #[must_use]
+    pub fn collider_from_ldtk(entity: &LdtkEntity, px_per_unit: f32, field_shape: &str) -> ShapeMoving {
+        let size = entity.size_px().as_vec2()/px_per_unit;
+        if entity.field_enums(field_shape).next().is_some_and(|v| v.eq_ignore_ascii_case("rectangle")) {
+            Rectangle::from_size(size).into()
+        } else {
+            Circle::new(size.min_element()/2.0).into()
+        }
+    }
+
     #[must_use]
     pub const fn origin(&self) -> Vec2 {
         self.origin
@@ -41,24 +204,128 @@ impl Pawn {
         self.origin = v;
     }
 
+    /// The origin at the start of the current simulation tick
+    #[must_use]
+    pub const fn origin_previous(&self) -> Vec2 {
+        self.origin_previous
+    }
+
+    /// Average velocity over the last simulation tick, from every move made during it, ie. including
+    /// carries and pushes
+    #[must_use]
+    pub const fn velocity(&self) -> Vec2 {
+        self.velocity
+    }
+
+    /// Moves the pawn without rendering it sliding there from its previous origin
+    pub const fn teleport(&mut self, v: Vec2) {
+        self.origin = v;
+        self.origin_previous = v;
+    }
+
+    /// Radius of the circle bounding the collider
     #[must_use]
-    pub const fn radius(&self) -> f32 {
-        self.collider.radius
+    pub fn radius(&self) -> f32 {
+        match self.collider {
+            ShapeMoving::Circle(s)    => s.radius,
+            ShapeMoving::Rectangle(s) => s.half_size.length(),
+        }
+    }
+
+    #[must_use]
+    pub const fn filter(&self) -> CollisionFilter {
+        self.filter
+    }
+
+    pub const fn set_filter(&mut self, v: CollisionFilter) {
+        self.filter = v;
     }
 
     #[must_use]
-    pub const fn layers(&self) -> u16 {
-        self.layers
+    pub const fn mass(&self) -> f32 {
+        self.mass
+    }
+
+    pub const fn set_mass(&mut self, v: f32) {
+        self.mass = v;
     }
     
 }
 
+/// Starts the tick, measuring each pawn's velocity over the last one
+pub fn pawn_store_previous_origin(
+    mut q_pawns: Query<&mut Pawn>,
+    r_time: Res<Time>,
+) {
+    let delta = r_time.delta_secs();
+    q_pawns.iter_mut().for_each(|mut p| {
+        // Only read for rendering and steering, flagging every pawn changed would re-register them all
+        let p = p.bypass_change_detection();
+        p.velocity = if delta > 0.0 { (p.origin - p.origin_previous)/delta } else { Vec2::ZERO };
+        p.origin_previous = p.origin;
+    });
+}
+
+/// Places pawns part way between their last two ticks' origins, by how far into the next tick the frame is
 pub fn sync_pawn_transform(
     mut q_pawns: Query<(&mut Transform, &Pawn)>,
-    // r_ppu: Res<PixelsPerUnit>
+    r_time: Res<Time<Fixed>>,
+    r_settings: Option<Res<PawnTransformSettings>>,
+    r_ppu: Option<Res<PixelsPerUnit>>,
 ) {
-    q_pawns.iter_mut().for_each(|(mut t, p)| t.translation = p.origin()
-        // .map(|v| ppu_snap_to(*r_ppu, v))
-        .extend(t.translation.z)
-    );
+    let settings = r_settings.map_or_else(PawnTransformSettings::default, |v| *v);
+    let alpha    = r_time.overstep_fraction();
+    let ppu      = r_ppu.map(|v| *v);
+
+    q_pawns.iter_mut().for_each(|(mut t, p)| {
+        let origin = settings.render_origin(p.origin_previous(), p.origin(), alpha, ppu);
+        t.translation = origin.extend(t.translation.z);
+    });
+}
+
+/// Outlines every pawn's collider where it's rendered, for debugging shapes that don't match their
+/// sprites. Enabled by [`PluginPawn::debug_draw`].
+pub fn pawn_debug_draw(
+    mut r_queue: ResMut<DebugDrawQueue>,
+    q_pawns: Query<&Pawn>,
+    r_time: Res<Time<Fixed>>,
+    r_settings: Option<Res<PawnTransformSettings>>,
+    r_ppu: Option<Res<PixelsPerUnit>>,
+) {
+    let settings = r_settings.map_or_else(PawnTransformSettings::default, |v| *v);
+    let alpha    = r_time.overstep_fraction();
+    let ppu      = r_ppu.map(|v| *v);
+
+    for pawn in &q_pawns {
+        let origin = settings.render_origin(pawn.origin_previous(), pawn.origin(), alpha, ppu);
+        r_queue.shape(origin, &pawn.collider(), DebugDrawOptions::default(), DebugDrawLifetime::single_frame());
+    }
+}
+
+pub fn register_pawn_collider(colliders: &mut CollisionMap, entity: Entity, pawn: &Pawn) {
+    colliders.remove_entity(entity);
+    colliders.insert_dynamic(pawn.origin(), pawn.collider(), pawn.filter(), Some(entity));
+}
+
+/// Keeps pawns registered as dynamic entries, for those spawned, despawned or moved outside [`PawnMove`]
+pub fn pawn_collider_sync(
+    mut r_collision_map: ResMut<CollisionMap>,
+    mut l_generation: Local<u64>,
+
+    q_pawns: Query<(Entity, &Pawn)>,
+    q_changed: Query<(Entity, &Pawn), Changed<Pawn>>,
+
+    mut ev_removed: RemovedComponents<Pawn>,
+) {
+    for entity in ev_removed.read() {
+        r_collision_map.remove_entity(entity);
+    }
+
+    // The map was cleared (ie. a level reload), everything needs re-registering
+    if *l_generation == r_collision_map.generation() {
+        q_changed.iter().for_each(|(e, p)| register_pawn_collider(&mut r_collision_map, e, p));
+    } else {
+        *l_generation = r_collision_map.generation();
+        q_pawns.iter().for_each(|(e, p)| register_pawn_collider(&mut r_collision_map, e, p));
+    }
 }
@@ -2,11 +2,11 @@
 
 use bevy::prelude::*;
 
-use raydee::prelude::{MotionFrame, MotionQuery, ShapeCommon, ShapeMoving};
+use raydee::prelude::{MotionFrame, MotionQuery, MotionTrace, ShapeCommon, ShapeMoving};
 
-use crate::collision::CollisionMap;
+use crate::{body::{push_body, DynamicBody}, collision::{CollisionFilter, CollisionHit, CollisionMap, CollisionMapEntry, ContactEvent, MotionQueryContext, SensorEvent}};
 
-use super::Pawn;
+use super::{register_pawn_collider, AvoidanceNeighbour, Pawn, PawnAvoidance, PawnContacts, PawnSensors};
 
 #[derive(Debug, Clone, Copy)]
 pub enum PawnMoveTarget {
@@ -30,6 +30,10 @@ pub struct PawnMove {
     pub entity: Entity,
     pub target: PawnMoveTarget,
     pub speed:  f32,
+    /// Overrides the pawn's filter for this move only
+    pub filter: Option<CollisionFilter>,
+    /// Steers around other pawns first, when the pawn has [`PawnAvoidance`]
+    pub steer:  bool,
 }
 
 impl PawnMove {
@@ -57,12 +61,12 @@ impl PawnMove {
 
     #[must_use]
     pub const fn absolute(entity: Entity, target: Vec2) -> Self {
-        Self { entity, target: PawnMoveTarget::Absolute(target), speed: 0.0 }
+        Self { entity, target: PawnMoveTarget::Absolute(target), speed: 0.0, filter: None, steer: false }
     }
     
     #[must_use]
     pub const fn relative(entity: Entity, target: Vec2) -> Self {
-        Self { entity, target: PawnMoveTarget::Relative(target), speed: 0.0 }
+        Self { entity, target: PawnMoveTarget::Relative(target), speed: 0.0, filter: None, steer: false }
     }
 
     #[must_use]
@@ -83,21 +87,78 @@ impl PawnMove {
         self
     }
 
+    #[must_use]
+    pub const fn with_filter(mut self, filter: CollisionFilter) -> Self {
+        self.filter = Some(filter);
+        self
+    }
+
+    #[must_use]
+    pub const fn with_steering(mut self) -> Self {
+        self.steer = true;
+        self
+    }
+
     pub fn do_deferred(self, commands: &mut Commands) {
         commands.run_system_cached_with(move_pawn, self);
     }
 
+    /// Moves the pawn right away, for exclusive systems that chain moves off each other's results
+    pub fn do_now(self, world: &mut World) {
+        world.run_system_cached_with(move_pawn, self).expect("move_pawn is always runnable");
+    }
+
 }
 
+#[derive(Debug, Default, Clone, Component)]
+pub struct PawnMotionTrace(pub MotionTrace);
+
+#[derive(Debug, Default, Component)]
+pub struct PawnMotionCache(pub MotionQueryContext);
+
+type PawnMoveQuery<'w, 's> = Query<'w, 's, (
+    &'static mut Pawn,
+    Option<&'static mut PawnMotionTrace>,
+    Option<&'static mut PawnMotionCache>,
+    Option<&'static mut PawnSensors>,
+    Option<&'static mut PawnContacts>,
+)>;
+
+const PAWN_SOLVE_ITERATIONS: usize = 4;
+pub(super) const PAWN_SKIN_DISTANCE: f32 = 1e-4;
+
+/// How many pawns deep a push can propagate, ie. a pawn pushing a pawn pushing a pawn. Bodies
+/// pushed along the way are limited separately, see [`push_body`].
+const PAWN_PUSH_DEPTH: usize = 3;
+
+/// How many times a pawn will push and re-solve its motion in a single move
+const PAWN_PUSH_ATTEMPTS: usize = 2;
+
 fn move_pawn(
     In(action): In<PawnMove>,
-    mut q_pawns: Query<&mut Pawn>,
-    r_colliders: Res<CollisionMap>,
+    mut q_pawns: PawnMoveQuery,
+    mut q_bodies: Query<&mut DynamicBody>,
+    q_avoidance: Query<&PawnAvoidance>,
+    mut r_colliders: ResMut<CollisionMap>,
+    mut ev_sensors: EventWriter<SensorEvent>,
+    mut ev_contacts: EventWriter<ContactEvent>,
+    r_time: Res<Time>,
 ) {
-    let mut pawn = q_pawns.get_mut(action.entity).unwrap();
-    let target = action.get_target(pawn.origin); 
-    let result = solve_motion(4, 1e-4, &r_colliders, pawn.layers(), pawn.collider, pawn.origin, target);
-    pawn.origin = result.position_start();
+    let (pawn, ..) = q_pawns.get(action.entity).unwrap();
+    let origin   = pawn.origin;
+    let velocity = pawn.velocity();
+    let delta    = r_time.delta_secs();
+
+    let mut target = action.get_target(origin);
+    if action.steer && delta > 0.0 {
+        if let Ok(avoidance) = q_avoidance.get(action.entity) {
+            let desired    = (target - origin)/delta;
+            let neighbours = avoidance_neighbours(action.entity, origin, avoidance, desired.length(), &q_pawns, &q_avoidance, &r_colliders);
+            target = origin + avoidance.steer(velocity, desired, &neighbours)*delta;
+        }
+    }
+
+    move_pawn_pushing(action.entity, target, action.filter, 0, &mut q_pawns, &mut q_bodies, &mut r_colliders, &mut ev_sensors, &mut ev_contacts);
 
     // let distance = result.position_start().distance(pawn.collider.origin);
     // if distance > movement.distance()*1.01 {
@@ -107,42 +168,179 @@ fn move_pawn(
 
 }
 
+/// Pawns near enough to the steering one to matter within its horizon, from the map's dynamic entries
+fn avoidance_neighbours(
+    entity: Entity,
+    origin: Vec2,
+    avoidance: &PawnAvoidance,
+    speed: f32,
+    q_pawns: &PawnMoveQuery,
+    q_avoidance: &Query<&PawnAvoidance>,
+    colliders: &CollisionMap,
+) -> Vec<AvoidanceNeighbour> {
+    let Ok((pawn, ..)) = q_pawns.get(entity) else { return Vec::new(); };
+    let reach = Vec2::splat(avoidance.reach(speed));
+
+    let mut result = Vec::new();
+    colliders.visit_filtered(origin - reach, origin + reach, pawn.filter(), |entry| {
+        let Some(other) = entry.entity.filter(|&v| entry.dynamic && v != entity) else { return; };
+        let Ok((other_pawn, ..)) = q_pawns.get(other) else { return; };
+
+        let other_avoidance = q_avoidance.get(other).ok();
+        result.push(AvoidanceNeighbour{
+            offset:   other_pawn.origin() - origin,
+            velocity: other_pawn.velocity(),
+            radius:   other_avoidance.map_or(other_pawn.radius(), PawnAvoidance::radius),
+            responsibility: avoidance.responsibility(other_avoidance.map(PawnAvoidance::priority)),
+        });
+    });
+    result
+}
+
+fn move_pawn_pushing(
+    entity: Entity,
+    target: Vec2,
+    filter: Option<CollisionFilter>,
+    depth: usize,
+    q_pawns: &mut PawnMoveQuery,
+    q_bodies: &mut Query<&mut DynamicBody>,
+    colliders: &mut CollisionMap,
+    ev_sensors: &mut EventWriter<SensorEvent>,
+    ev_contacts: &mut EventWriter<ContactEvent>,
+) {
+    let mut context_local = MotionQueryContext::default();
+
+    for attempt in 0..=PAWN_PUSH_ATTEMPTS {
+        let Ok((pawn, trace, cache, ..)) = q_pawns.get_mut(entity) else { return; };
+        let context = cache.map_or(&mut context_local, |v| &mut v.into_inner().0);
+        let trace   = trace.map(|v| {
+            let trace = &mut v.into_inner().0;
+            trace.clear();
+            trace
+        });
+
+        let filter = filter.unwrap_or(pawn.filter());
+        solve_motion(PAWN_SOLVE_ITERATIONS, PAWN_SKIN_DISTANCE, colliders, filter, pawn.collider, pawn.origin, target, Some(entity), context, trace);
+
+        if attempt == PAWN_PUSH_ATTEMPTS || depth == PAWN_PUSH_DEPTH {
+            break;
+        }
+
+        // The first pawn or body we ran into, and how much further we wanted to go into it
+        let mass = pawn.mass();
+        let Some((other, push)) = context.contacts().iter().find_map(|v| {
+            let other = v.entry.entity.filter(|_| v.entry.dynamic)?;
+            Some((other, v.push_towards(target)?))
+        }) else { break; };
+
+        // Heavier or equal pawns and bodies block, lighter ones are shoved out of the way
+        let moved = if let Ok((other_pawn, ..)) = q_pawns.get(other) {
+            if other_pawn.mass() >= mass {
+                break;
+            }
+
+            let other_origin = other_pawn.origin();
+            move_pawn_pushing(other, other_origin + push, None, depth + 1, q_pawns, q_bodies, colliders, ev_sensors, ev_contacts);
+            q_pawns.get(other).is_ok_and(|(v, ..)| v.origin() != other_origin)
+        } else if let Ok(other_body) = q_bodies.get(other) {
+            if other_body.mass() >= mass {
+                break;
+            }
+
+            push_body(other, push, 0, q_bodies, colliders) != Vec2::ZERO
+        } else {
+            false
+        };
+
+        if !moved {
+            break;
+        }
+    }
+
+    let Ok((mut pawn, _, cache, sensors, contacts)) = q_pawns.get_mut(entity) else { return; };
+    let context = cache.map_or(&context_local, |v| &v.into_inner().0);
+    // Registered right away, so pawn_collider_sync doesn't need to see the change
+    let pawn = pawn.bypass_change_detection();
+    pawn.origin = context.path().last().copied().unwrap_or(pawn.origin);
+    register_pawn_collider(colliders, entity, pawn);
+
+    if let Some(mut sensors) = sensors {
+        sensors.update(entity, context, |ev| { ev_sensors.send(ev); });
+    }
+
+    if let Some(mut contacts) = contacts {
+        contacts.update(context.contacts());
+    }
+
+    ev_contacts.send_batch(context.contacts().iter().map(|&contact| ContactEvent{pawn: entity, contact}));
+}
 
-fn solve_motion(
+pub fn solve_motion(
     iteration_limit: usize,
     skin_distance: f32,
     colliders: &CollisionMap,
-    layers: u16,
+    filter: CollisionFilter,
     collider: impl Into<ShapeMoving>,
     origin: Vec2,
-    target: Vec2
+    target: Vec2,
+    ignore: Option<Entity>,
+    context: &mut MotionQueryContext,
+    mut trace: Option<&mut MotionTrace>,
 ) -> MotionFrame {
 
     let mut curr_motion = MotionFrame::new_from_target(collider, origin, target);
     let mut iter_remaining = if curr_motion.distance() > 0.0 { iteration_limit } else { 1 };
 
+    // Sliding never travels further than the initial distance, so this covers every iteration
+    let bbox  = curr_motion.collider().bounding_box();
+    let reach = Vec2::splat(curr_motion.distance());
+    let bounds = [origin - reach + bbox[0], origin + reach + bbox[1]];
+    context.prepare(colliders, filter, curr_motion.collider(), skin_distance, bounds);
+    context.prepare_dynamic(colliders, filter, curr_motion.collider(), skin_distance, bounds, ignore);
+    context.begin_path(origin);
+
     while curr_motion.distance() > 0.0 {
         if iter_remaining == 0 {
-            bevy::log::debug!("Exceeded movement solve iteration limit");
+            if let Some(trace) = trace.as_deref_mut() {
+                trace.set_limit_exceeded();
+                bevy::log::debug!("Exceeded movement solve iteration limit\n{trace}");
+            } else {
+                bevy::log::debug!("Exceeded movement solve iteration limit");
+            }
             break;
         }
         iter_remaining -= 1;
 
-        let bbox  = curr_motion.collider().bounding_box();
         let start = curr_motion.position_start();
         let end   = curr_motion.position_end();
 
-        let min = start.min(end)+bbox[0];
-        let max = start.max(end)+bbox[1];
+        let min = start.min(end);
+        let max = start.max(end);
 
-        let mut query = MotionQuery::new(curr_motion, skin_distance);
+        if let Some(trace) = trace.as_deref_mut() {
+            trace.begin_iteration(curr_motion, [min + bbox[0], max + bbox[1]]);
+        }
+
+        let mut query = MotionQuery::<CollisionMapEntry>::with_source(curr_motion, skin_distance);
 
-        (0..16).filter(|i| (layers & (i << 1)) == (i << 1))
-            .filter_map(|i| colliders.try_get(i as usize))
-            .for_each(|l| l.visit(min, max, |v| v.iter().for_each(|e| query.test(e.origin, &e.collider))));
+        context.visit(min, max).for_each(|candidate| {
+            let result = query.test_combined_with(candidate.entry, candidate.entry.origin, &candidate.combined);
+            if let Some(trace) = trace.as_deref_mut() {
+                let index = trace.record_candidate(candidate.entry.origin, candidate.entry.collider);
+                trace.record_test(index, result);
+            }
+        });
 
         let mut next_motion = query.result();
         next_motion.set_distance(next_motion.distance() * Vec2::dot(curr_motion.direction(), next_motion.direction()).abs());
+
+        if let Some(trace) = trace.as_deref_mut() {
+            trace.end_iteration(next_motion);
+        }
+
+        let contact = query.hit().zip(query.hit_source()).map(|(hit, entry)| CollisionHit{entry, hit});
+        context.extend_path(next_motion.position_start(), contact);
+
         curr_motion = next_motion;
     }
     
@@ -0,0 +1,422 @@
+// Copyright 2025 Natalie Baker // AGPLv3 //
+
+use bevy::prelude::*;
+
+use crate::collision::{CollisionHit, CollisionMap};
+
+use super::{Pawn, PawnContacts, PawnController, PawnMove};
+
+/// How far below a pawn the ground is looked for, before it counts as airborne
+const PLATFORMER_GROUND_PROBE: f32 = 0.02;
+
+/// Side-view movement for a pawn's [`PawnController`], which is limited to horizontal input while
+/// the platformer handles gravity and jumping, see [`pawn_platformer_move`].
+///
+/// Ground is found by casting the pawn's collider downwards, and is any surface no steeper than
+/// the max slope, ie. shallow enough `Ramp`s. Grounded pawns walk along the ground, snap down onto
+/// it when it falls away beneath them, and step up ledges no taller than the step height.
+#[derive(Debug, Clone, Copy, Component)]
+pub struct PawnPlatformer {
+    gravity:          f32,
+    fall_speed_max:   f32,
+    jump_speed:       f32,
+    jump_cut:         f32,
+    coyote_time:      f32,
+    jump_buffer_time: f32,
+    slope_max:        f32,
+    step_height:      f32,
+    snap_distance:    f32,
+    jump_held:        bool,
+    jumping:          bool,
+    ground:           Option<Vec2>,
+    coyote:           f32,
+    buffer:           f32,
+}
+
+impl Default for PawnPlatformer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PawnPlatformer {
+
+    pub const DEFAULT_GRAVITY:          f32 = 30.0;
+    pub const DEFAULT_FALL_SPEED_MAX:   f32 = 20.0;
+    pub const DEFAULT_JUMP_SPEED:       f32 = 10.0;
+    pub const DEFAULT_JUMP_CUT:         f32 = 0.5;
+    pub const DEFAULT_COYOTE_TIME:      f32 = 0.1;
+    pub const DEFAULT_JUMP_BUFFER_TIME: f32 = 0.1;
+    pub const DEFAULT_SLOPE_MAX:        f32 = core::f32::consts::FRAC_PI_4 + 0.01;
+    pub const DEFAULT_STEP_HEIGHT:      f32 = 0.25;
+    pub const DEFAULT_SNAP_DISTANCE:    f32 = 0.25;
+
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            gravity:          Self::DEFAULT_GRAVITY,
+            fall_speed_max:   Self::DEFAULT_FALL_SPEED_MAX,
+            jump_speed:       Self::DEFAULT_JUMP_SPEED,
+            jump_cut:         Self::DEFAULT_JUMP_CUT,
+            coyote_time:      Self::DEFAULT_COYOTE_TIME,
+            jump_buffer_time: Self::DEFAULT_JUMP_BUFFER_TIME,
+            slope_max:        Self::DEFAULT_SLOPE_MAX,
+            step_height:      Self::DEFAULT_STEP_HEIGHT,
+            snap_distance:    Self::DEFAULT_SNAP_DISTANCE,
+            jump_held:        false,
+            jumping:          false,
+            ground:           None,
+            coyote:           0.0,
+            buffer:           0.0,
+        }
+    }
+
+    /// Units per second squared, and the fastest a pawn can fall in units per second
+    #[must_use]
+    pub const fn with_gravity(mut self, gravity: f32, fall_speed_max: f32) -> Self {
+        self.gravity        = gravity;
+        self.fall_speed_max = fall_speed_max;
+        self
+    }
+
+    /// Upwards speed at the start of a jump, and what's kept of it if the jump is released early
+    #[must_use]
+    pub const fn with_jump(mut self, speed: f32, cut: f32) -> Self {
+        self.jump_speed = speed;
+        self.jump_cut   = cut;
+        self
+    }
+
+    /// Seconds after leaving the ground a jump is still allowed, and seconds before landing a jump is remembered
+    #[must_use]
+    pub const fn with_jump_timing(mut self, coyote_time: f32, buffer_time: f32) -> Self {
+        self.coyote_time      = coyote_time;
+        self.jump_buffer_time = buffer_time;
+        self
+    }
+
+    /// Steepest walkable surface, in radians from flat
+    #[must_use]
+    pub const fn with_slope_max(mut self, slope_max: f32) -> Self {
+        self.slope_max = slope_max;
+        self
+    }
+
+    #[must_use]
+    pub const fn with_step_height(mut self, step_height: f32) -> Self {
+        self.step_height = step_height;
+        self
+    }
+
+    #[must_use]
+    pub const fn with_snap_distance(mut self, snap_distance: f32) -> Self {
+        self.snap_distance = snap_distance;
+        self
+    }
+
+    #[must_use]
+    pub const fn gravity(&self) -> f32 {
+        self.gravity
+    }
+
+    #[must_use]
+    pub const fn jump_speed(&self) -> f32 {
+        self.jump_speed
+    }
+
+    #[must_use]
+    pub const fn slope_max(&self) -> f32 {
+        self.slope_max
+    }
+
+    #[must_use]
+    pub const fn step_height(&self) -> f32 {
+        self.step_height
+    }
+
+    #[must_use]
+    pub const fn snap_distance(&self) -> f32 {
+        self.snap_distance
+    }
+
+    #[must_use]
+    pub const fn grounded(&self) -> bool {
+        self.ground.is_some()
+    }
+
+    /// Normal of the ground the pawn stood on as of the last tick
+    #[must_use]
+    pub const fn ground_normal(&self) -> Option<Vec2> {
+        self.ground
+    }
+
+    /// True from a jump until landing, or until it's cut short
+    #[must_use]
+    pub const fn jumping(&self) -> bool {
+        self.jumping
+    }
+
+    #[must_use]
+    pub const fn jump_held(&self) -> bool {
+        self.jump_held
+    }
+
+    /// Whether the jump button is down, to be set every tick. Pressing it buffers a jump, and
+    /// releasing it cuts a rising jump short.
+    pub const fn set_jump_held(&mut self, v: bool) {
+        if v && !self.jump_held {
+            self.press_jump();
+        }
+        self.jump_held = v;
+    }
+
+    /// Jumps as soon as the pawn is able to, within the buffer time
+    pub const fn press_jump(&mut self) {
+        self.buffer = self.jump_buffer_time;
+    }
+
+    #[must_use]
+    pub fn is_walkable(&self, normal: Vec2) -> bool {
+        normal.y >= self.slope_max.cos()
+    }
+
+    /// Removes velocity into walls and ceilings, while walkable surfaces only stop the pawn falling
+    fn remove_blocked(&self, mut velocity: Vec2, normals: impl IntoIterator<Item = Vec2>) -> Vec2 {
+        for normal in normals {
+            if self.is_walkable(normal) {
+                velocity.y = velocity.y.max(0.0);
+            } else {
+                let into = velocity.dot(normal);
+                if into < 0.0 {
+                    velocity -= normal*into;
+                }
+            }
+        }
+        velocity
+    }
+
+    /// Starts a tick on the ground found beneath the pawn, if it's walkable. Landing stops the fall
+    /// and refills the coyote time, which runs down in the air along with any buffered jump.
+    fn update_ground(&mut self, ground: Option<Vec2>, mut velocity: Vec2, delta: f32) -> Vec2 {
+        self.ground = ground.filter(|&v| self.is_walkable(v));
+        if self.ground.is_some() && velocity.y <= 0.0 {
+            velocity.y   = 0.0;
+            self.jumping = false;
+            self.coyote  = self.coyote_time;
+        } else {
+            // Airborne, or rising from a jump that hasn't left the ground behind yet
+            self.ground  = None;
+            self.coyote -= delta;
+        }
+        self.buffer -= delta;
+        velocity
+    }
+
+    /// Jumps when one is buffered within the coyote time, cuts released jumps short, then falls
+    fn update_jump(&mut self, mut velocity: Vec2, delta: f32) -> Vec2 {
+        if self.buffer > 0.0 && self.coyote > 0.0 {
+            velocity.y   = self.jump_speed;
+            self.jumping = true;
+            self.ground  = None;
+            self.buffer  = 0.0;
+            self.coyote  = 0.0;
+        } else if self.jumping && !self.jump_held && velocity.y > 0.0 {
+            velocity.y  *= self.jump_cut;
+            self.jumping = false;
+        }
+
+        if self.ground.is_none() {
+            velocity.y = (velocity.y - self.gravity*delta).max(-self.fall_speed_max);
+        }
+        velocity
+    }
+
+    /// Moves along the ground by the displacement, stepping up onto ledges and snapping down onto
+    /// the ground as needed. Each cast starts from where the moves before it actually left the pawn.
+    fn walk(&self, world: &mut World, entity: Entity, displacement: Vec2) {
+        let Some(pawn) = world.get::<Pawn>(entity).cloned() else { return; };
+        let origin   = pawn.origin();
+        let forward  = Vec2::new(displacement.x.signum(), 0.0);
+        let distance = displacement.x.abs();
+
+        // Ledge: rise over it, cross, then let the snap put the pawn down on top
+        let step = {
+            let colliders = world.resource::<CollisionMap>();
+            let blocked = distance > 0.0 && cast(colliders, entity, &pawn, origin, forward, distance)
+                .is_some_and(|v| !self.is_walkable(v.hit.normal));
+
+            let raised = origin + Vec2::Y*self.step_height;
+            blocked
+                && cast(colliders, entity, &pawn, origin, Vec2::Y, self.step_height).is_none()
+                && cast(colliders, entity, &pawn, raised, forward, distance).is_none()
+        };
+
+        let snap = if step {
+            PawnMove::relative(entity, Vec2::Y*self.step_height).do_now(world);
+            let risen = world.get::<Pawn>(entity).map_or(0.0, |v| v.origin().y - origin.y);
+            PawnMove::relative(entity, forward*distance).do_now(world);
+            risen.max(0.0) + self.snap_distance
+        } else {
+            PawnMove::relative(entity, displacement).do_now(world);
+            self.snap_distance
+        };
+
+        let Some(pawn) = world.get::<Pawn>(entity).cloned() else { return; };
+        let colliders = world.resource::<CollisionMap>();
+        let hit = cast(colliders, entity, &pawn, pawn.origin(), Vec2::NEG_Y, snap).filter(|v| self.is_walkable(v.hit.normal));
+        if let Some(hit) = hit.filter(|v| v.distance() > 0.0) {
+            PawnMove::relative(entity, Vec2::NEG_Y*hit.distance()).do_now(world);
+        }
+    }
+
+}
+
+/// Walks a grounded platformer pawn, once the moves queued before it have been applied, see [`PawnPlatformer::walk`]
+fn platformer_walk(
+    In((entity, displacement, platformer)): In<(Entity, Vec2, PawnPlatformer)>,
+    world: &mut World,
+) {
+    platformer.walk(world, entity, displacement);
+}
+
+/// The first solid collider, other than the pawn itself, hit moving the pawn's collider along the direction
+fn cast(colliders: &CollisionMap, entity: Entity, pawn: &Pawn, origin: Vec2, direction: Vec2, distance: f32) -> Option<CollisionHit> {
+    colliders.shape_cast_all(pawn.collider(), origin, direction, distance, pawn.filter())
+        .into_iter()
+        .find(|v| !v.entry.sensor && v.entry.entity != Some(entity))
+}
+
+/// Finds the ground, then accelerates platformer pawns horizontally, jumps and falls, and moves them
+pub fn pawn_platformer_move(
+    mut commands: Commands,
+    mut q_pawns: Query<(Entity, &Pawn, &mut PawnPlatformer, &mut PawnController, Option<&PawnContacts>)>,
+    r_colliders: Res<CollisionMap>,
+    r_time: Res<Time>,
+) {
+    let delta = r_time.delta_secs();
+    for (entity, pawn, mut platformer, mut controller, contacts) in &mut q_pawns {
+        let platformer = &mut *platformer;
+        let ground = cast(&r_colliders, entity, pawn, pawn.origin(), Vec2::NEG_Y, PLATFORMER_GROUND_PROBE).map(|v| v.hit.normal);
+
+        let mut velocity = controller.velocity();
+        if let Some(contacts) = contacts {
+            velocity = platformer.remove_blocked(velocity, contacts.normals());
+        }
+        let velocity = platformer.update_ground(ground, velocity, delta);
+
+        controller.set_velocity(velocity);
+        controller.set_grounded(platformer.ground.is_some());
+        controller.accelerate_axes(delta, BVec2::new(true, false));
+        let velocity = platformer.update_jump(controller.velocity(), delta);
+        controller.set_velocity(velocity);
+
+        // Walk along the ground's tangent, so slopes are climbed and descended at full speed
+        match platformer.ground {
+            Some(normal) => {
+                let displacement = Vec2::new(normal.y, -normal.x)*velocity.x*delta;
+                if displacement != Vec2::ZERO {
+                    commands.run_system_cached_with(platformer_walk, (entity, displacement, *platformer));
+                }
+            },
+            None => if velocity != Vec2::ZERO {
+                PawnMove::relative(entity, velocity*delta).do_deferred(&mut commands);
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::math::Vec2;
+
+    use super::PawnPlatformer;
+
+    const DT: f32 = 1.0/60.0;
+
+    /// The vertical half of a platformer tick, standing on the ground normal if given
+    fn tick(platformer: &mut PawnPlatformer, ground: Option<Vec2>, velocity: Vec2) -> Vec2 {
+        let velocity = platformer.update_ground(ground, velocity, DT);
+        platformer.update_jump(velocity, DT)
+    }
+
+    /// Ticks through the air, returning the velocity afterwards
+    fn fall(platformer: &mut PawnPlatformer, ticks: usize) -> Vec2 {
+        (0..ticks).fold(Vec2::ZERO, |v, _| tick(platformer, None, v))
+    }
+
+    #[test]
+    fn coyote_time_allows_jumps_just_after_leaving_the_ground() {
+        let mut late = PawnPlatformer::new();
+        tick(&mut late, Some(Vec2::Y), Vec2::ZERO);
+        let velocity = fall(&mut late, 3);
+        late.set_jump_held(true);
+        let velocity = tick(&mut late, None, velocity);
+        assert!(late.jumping() && velocity.y > 0.0);
+
+        let mut too_late = PawnPlatformer::new();
+        tick(&mut too_late, Some(Vec2::Y), Vec2::ZERO);
+        let velocity = fall(&mut too_late, 10);
+        too_late.set_jump_held(true);
+        let velocity = tick(&mut too_late, None, velocity);
+        assert!(!too_late.jumping() && velocity.y < 0.0);
+    }
+
+    #[test]
+    fn jump_buffer_remembers_presses_just_before_landing() {
+        let mut early = PawnPlatformer::new();
+        early.set_jump_held(true);
+        let velocity = fall(&mut early, 3);
+        assert!(!early.jumping(), "no jump without ground or coyote time");
+        let velocity = tick(&mut early, Some(Vec2::Y), velocity);
+        assert!(early.jumping() && velocity.y > 0.0);
+
+        let mut too_early = PawnPlatformer::new();
+        too_early.set_jump_held(true);
+        let velocity = fall(&mut too_early, 10);
+        let velocity = tick(&mut too_early, Some(Vec2::Y), velocity);
+        assert!(!too_early.jumping() && too_early.grounded());
+        assert!(velocity.y.abs() < 1e-6);
+    }
+
+    #[test]
+    fn releasing_jump_cuts_it_short() {
+        let mut held = PawnPlatformer::new();
+        held.set_jump_held(true);
+        let rising = tick(&mut held, Some(Vec2::Y), Vec2::ZERO);
+        assert!(held.jumping());
+
+        let mut released = held;
+        released.set_jump_held(false);
+
+        let velocity_held     = tick(&mut held, None, rising);
+        let velocity_released = tick(&mut released, None, rising);
+        let gravity = PawnPlatformer::DEFAULT_GRAVITY*DT;
+
+        assert!(held.jumping() && !released.jumping());
+        assert!((velocity_held.y     - (rising.y - gravity)).abs() < 1e-4);
+        assert!((velocity_released.y - (rising.y*PawnPlatformer::DEFAULT_JUMP_CUT - gravity)).abs() < 1e-4);
+    }
+
+    #[test]
+    fn slopes_steeper_than_the_limit_are_not_ground() {
+        // Normal of ground rising at the angle from flat
+        let slope = |degrees: f32| Vec2::from_angle(degrees.to_radians()).perp();
+        let shallow = slope(45.0);
+        let steep   = slope(60.0);
+        assert!(shallow.y > 0.0 && steep.y > 0.0);
+
+        let mut platformer = PawnPlatformer::new();
+        assert!(platformer.is_walkable(shallow));
+        assert!(!platformer.is_walkable(steep));
+
+        tick(&mut platformer, Some(steep), Vec2::ZERO);
+        assert!(!platformer.grounded());
+
+        tick(&mut platformer, Some(shallow), Vec2::ZERO);
+        assert!(platformer.grounded());
+
+        let mut strict = PawnPlatformer::new().with_slope_max(30_f32.to_radians());
+        tick(&mut strict, Some(shallow), Vec2::ZERO);
+        assert!(!strict.grounded());
+    }
+}
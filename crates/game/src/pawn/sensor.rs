@@ -0,0 +1,151 @@
+// Copyright 2025 Natalie Baker // AGPLv3 //
+
+use bevy::prelude::*;
+use raydee::prelude::{MotionQuery, RayTarget, ShapeCommon, ShapeMoving};
+
+use crate::collision::{CollisionFilter, CollisionMap, CollisionMapID, MotionQueryContext, SensorEvent, SensorTransition};
+
+use super::{movement::PAWN_SKIN_DISTANCE, Pawn};
+
+/// Sensors the pawn currently overlaps, pawns without this component don't report sensor events
+#[derive(Debug, Default, Clone, Component)]
+pub struct PawnSensors {
+    overlapping: Vec<(CollisionMapID, Option<Entity>)>,
+    updated:     bool,
+}
+
+impl PawnSensors {
+
+    pub fn iter(&self) -> impl Iterator<Item = (CollisionMapID, Option<Entity>)> + '_ {
+        self.overlapping.iter().copied()
+    }
+
+    #[must_use]
+    pub fn contains(&self, identifier: CollisionMapID) -> bool {
+        self.overlapping.iter().any(|(v, _)| *v == identifier)
+    }
+
+    /// Whether [`PawnSensors::update`] has run since the last [`pawn_sensors_update_idle`]
+    #[must_use]
+    pub const fn updated(&self) -> bool {
+        self.updated
+    }
+
+    /// Compares the sensors touched by the context's last solved path against those previously overlapped
+    pub fn update(&mut self, pawn: Entity, context: &MotionQueryContext, send: impl FnMut(SensorEvent)) {
+        let path = context.path();
+        let Some(&end) = path.last() else { return; };
+
+        let candidates = context.sensors().iter().map(|candidate| {
+            let origin = candidate.entry.origin;
+            let inside = candidate.combined.contains_point(origin, end);
+            let swept  = !inside && path.windows(2).any(|v| candidate.combined.sweep_touches(origin, v[0], v[1]));
+            (candidate.entry.identifier, candidate.entry.entity, inside, swept)
+        }).collect::<Vec<_>>();
+
+        self.apply(pawn, &candidates, send);
+        self.updated = true;
+    }
+
+    /// As [`PawnSensors::update`], for a pawn resting at the origin, ie. one that didn't move this
+    /// tick while sensors may have moved onto or off it
+    pub fn update_at(&mut self, pawn: Entity, colliders: &CollisionMap, collider: ShapeMoving, origin: Vec2, filter: CollisionFilter, send: impl FnMut(SensorEvent)) {
+        let [min, max] = collider.bounding_box();
+        let mut candidates = Vec::<(CollisionMapID, Option<Entity>, bool, bool)>::new();
+        colliders.visit_filtered(origin + min, origin + max, filter, |entry| {
+            // Entries are stored in every chunk they touch
+            if entry.sensor && !candidates.iter().any(|v| v.0 == entry.identifier) {
+                let combined = MotionQuery::combine(collider, PAWN_SKIN_DISTANCE, &entry.collider);
+                candidates.push((entry.identifier, entry.entity, combined.contains_point(entry.origin, origin), false));
+            }
+        });
+
+        self.apply(pawn, &candidates, send);
+    }
+
+    /// Sends the transitions from the previously overlapped sensors to the candidates, each of
+    /// which is inside or not, and whether it was swept through on the way
+    fn apply(&mut self, pawn: Entity, candidates: &[(CollisionMapID, Option<Entity>, bool, bool)], mut send: impl FnMut(SensorEvent)) {
+        let mut send_transition = |identifier, sensor, transition| send(SensorEvent{pawn, sensor, identifier, transition});
+
+        let mut overlapping = Vec::with_capacity(self.overlapping.len());
+        for &(identifier, sensor, is_inside, swept) in candidates {
+            let was_inside = self.contains(identifier);
+            if is_inside {
+                overlapping.push((identifier, sensor));
+            }
+
+            match (was_inside, is_inside) {
+                (true,  true ) => send_transition(identifier, sensor, SensorTransition::Stayed),
+                (true,  false) => send_transition(identifier, sensor, SensorTransition::Exited),
+                (false, true ) => send_transition(identifier, sensor, SensorTransition::Entered),
+                (false, false) => if swept {
+                    send_transition(identifier, sensor, SensorTransition::Entered);
+                    send_transition(identifier, sensor, SensorTransition::Exited);
+                },
+            }
+        }
+
+        // Previously overlapped sensors that have since been removed from the map, or moved away
+        for &(identifier, sensor) in &self.overlapping {
+            if !candidates.iter().any(|v| v.0 == identifier) {
+                send_transition(identifier, sensor, SensorTransition::Exited);
+            }
+        }
+
+        self.overlapping = overlapping;
+    }
+
+}
+
+/// Checks the sensors of pawns that didn't move this tick, so those standing still still see
+/// sensors arrive, leave, or stay. Runs once every move, carry and platform has been applied.
+pub fn pawn_sensors_update_idle(
+    mut q_pawns: Query<(Entity, &Pawn, &mut PawnSensors)>,
+    r_collision_map: Res<CollisionMap>,
+    mut ev_sensors: EventWriter<SensorEvent>,
+) {
+    for (entity, pawn, mut sensors) in &mut q_pawns {
+        if sensors.updated {
+            sensors.updated = false;
+            continue;
+        }
+
+        sensors.update_at(entity, &r_collision_map, pawn.collider(), pawn.origin(), pawn.filter(), |ev| { ev_sensors.send(ev); });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+    use raydee::prelude::ShapeMoving;
+
+    use crate::collision::{CollisionFilter, CollisionMap, SensorTransition};
+
+    use super::PawnSensors;
+
+    fn update(sensors: &mut PawnSensors, map: &CollisionMap, origin: Vec2) -> Vec<SensorTransition> {
+        let mut transitions = Vec::new();
+        let collider = ShapeMoving::from(Circle::new(0.5));
+        sensors.update_at(Entity::PLACEHOLDER, map, collider, origin, CollisionFilter::DEFAULT, |ev| transitions.push(ev.transition));
+        transitions
+    }
+
+    #[test]
+    fn idle_pawn_sees_sensors_arrive_stay_and_leave() {
+        let mut map = CollisionMap::default();
+        let mut sensors = PawnSensors::default();
+        assert!(update(&mut sensors, &map, Vec2::ZERO).is_empty());
+
+        let sensor = map.insert_sensor(Vec2::new(0.5, 0.0), Rectangle::new(1.0, 1.0), CollisionFilter::DEFAULT, None);
+        assert_eq!(update(&mut sensors, &map, Vec2::ZERO), [SensorTransition::Entered]);
+        assert_eq!(update(&mut sensors, &map, Vec2::ZERO), [SensorTransition::Stayed]);
+        assert!(sensors.contains(sensor));
+
+        // Moved away, as a sensor platform would
+        map.remove(sensor);
+        map.insert_sensor(Vec2::new(4.0, 0.0), Rectangle::new(1.0, 1.0), CollisionFilter::DEFAULT, None);
+        assert_eq!(update(&mut sensors, &map, Vec2::ZERO), [SensorTransition::Exited]);
+        assert!(sensors.iter().next().is_none());
+    }
+}
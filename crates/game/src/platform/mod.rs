@@ -0,0 +1,305 @@
+// Copyright 2025 Natalie Baker // AGPLv3 //
+
+use bevy::prelude::*;
+use raydee::prelude::*;
+
+use crate::{body::body_integrate, collision::{CollisionFilter, CollisionMap}, pawn::{Pawn, PawnControlSystems, PawnMove, PawnTransformSettings}, scale::PixelsPerUnit};
+
+mod path;
+pub use path::*;
+
+pub struct PluginPlatform;
+
+impl Plugin for PluginPlatform {
+    fn build(&self, app: &mut App) {
+        app
+            .add_event::<PlatformCrushEvent>()
+            .configure_sets(FixedUpdate, PlatformSystems.before(PawnControlSystems).before(body_integrate))
+            // The sync point between moving and registering applies the carry moves, so they're
+            // solved while the moving platforms are out of the map, and crushes found after
+            .add_systems(FixedUpdate, (platform_follow_path, platform_move, platform_register, platform_detect_crush).chain().in_set(PlatformSystems))
+            .add_systems(PostUpdate, sync_platform_transform);
+    }
+}
+
+/// Systems moving platforms and their carried pawns. Moving platforms are out of the
+/// [`CollisionMap`] partway through, so anything else moving pawns or bodies should run after.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, SystemSet)]
+pub struct PlatformSystems;
+
+/// Sent each tick a pawn is left overlapping a platform, usually squeezed between it and a wall
+#[derive(Debug, Clone, Copy, Event)]
+pub struct PlatformCrushEvent {
+    pub pawn:        Entity,
+    pub platform:    Entity,
+    pub penetration: f32,
+}
+
+/// A collider moved by paths or scripts, via [`KinematicPlatform::set_origin`], that carries the
+/// pawns touching it. Conveyors can be made by giving a stationary platform a surface velocity.
+#[derive(Debug, Clone, Component)]
+pub struct KinematicPlatform {
+    collider: ShapeStatic,
+    filter:   CollisionFilter,
+    sensor:   bool,
+    origin:   Vec2,
+    /// Where the last [`platform_move`] left it, which the next measures its move from
+    origin_moved:     Vec2,
+    origin_previous:  Vec2,
+    delta:            Vec2,
+    surface_velocity: Vec2,
+}
+
+impl KinematicPlatform {
+
+    /// How close a pawn must be to count as touching, and so be carried
+    pub const CARRY_DISTANCE: f32 = 0.02;
+
+    /// How far a pawn can overlap a platform before it's considered crushed
+    pub const CRUSH_TOLERANCE: f32 = 0.05;
+
+    #[must_use]
+    pub fn new(origin: Vec2, collider: impl Into<ShapeStatic>, filter: CollisionFilter) -> Self {
+        Self {
+            collider: collider.into(),
+            filter,
+            sensor: false,
+            origin,
+            origin_moved: origin,
+            origin_previous: origin,
+            delta: Vec2::ZERO,
+            surface_velocity: Vec2::ZERO,
+        }
+    }
+
+    /// Pawns overlapping a sensor platform are carried without it blocking them, ie. a conveyor floor
+    #[must_use]
+    pub const fn as_sensor(mut self) -> Self {
+        self.sensor = true;
+        self
+    }
+
+    #[must_use]
+    pub const fn with_surface_velocity(mut self, surface_velocity: Vec2) -> Self {
+        self.surface_velocity = surface_velocity;
+        self
+    }
+
+    #[must_use]
+    pub const fn collider(&self) -> ShapeStatic {
+        self.collider
+    }
+
+    #[must_use]
+    pub const fn filter(&self) -> CollisionFilter {
+        self.filter
+    }
+
+    #[must_use]
+    pub const fn sensor(&self) -> bool {
+        self.sensor
+    }
+
+    #[must_use]
+    pub const fn origin(&self) -> Vec2 {
+        self.origin
+    }
+
+    /// Moves the platform, taking effect on the next [`platform_move`]
+    pub const fn set_origin(&mut self, v: Vec2) {
+        self.origin = v;
+    }
+
+    /// Where the platform was at the start of the current simulation tick
+    #[must_use]
+    pub const fn origin_previous(&self) -> Vec2 {
+        self.origin_previous
+    }
+
+    /// How far the platform moved in the last [`platform_move`]
+    #[must_use]
+    pub const fn delta(&self) -> Vec2 {
+        self.delta
+    }
+
+    #[must_use]
+    pub const fn surface_velocity(&self) -> Vec2 {
+        self.surface_velocity
+    }
+
+    pub const fn set_surface_velocity(&mut self, v: Vec2) {
+        self.surface_velocity = v;
+    }
+
+    /// Motion a pawn at the given origin receives from the platform moving by `delta`, if any
+    #[must_use]
+    pub fn carry_motion(&self, pawn: &Pawn, from: Vec2, delta: Vec2, surface: Vec2) -> Option<Vec2> {
+        let shape = pawn.collider();
+
+        // Touching before the move, ride along with it
+        let touching = ShapeCombined::between_moving_and_static(&shape.grown_by(Self::CARRY_DISTANCE), &self.collider);
+        if touching.contains_point(from, pawn.origin()) {
+            return Some(delta + surface);
+        }
+
+        // In the way of the move, pushed by whatever's left after the platform reaches it
+        let distance = delta.length();
+        if self.sensor || distance <= 0.0 {
+            return None;
+        }
+
+        let direction = delta/distance;
+        let combined  = ShapeCombined::between_moving_and_static(&shape, &self.collider);
+        combined.raycast_enter(from, &RayCaster::new(pawn.origin(), -direction))
+            .filter(|v| v.distance >= 0.0 && v.distance < distance)
+            .map(|v| direction*(distance - v.distance))
+    }
+
+}
+
+pub fn platform_follow_path(
+    mut q_platforms: Query<(&mut KinematicPlatform, &mut PlatformPath)>,
+    r_time: Res<Time>,
+) {
+    let delta = r_time.delta_secs();
+    q_platforms.iter_mut().for_each(|(mut platform, mut path)| {
+        let distance = path.speed()*delta;
+        platform.origin = path.advance(platform.origin, distance);
+    });
+}
+
+/// Carries or pushes the pawns touching moving platforms, taking the platforms out of the
+/// [`CollisionMap`] until [`platform_register`] so they don't block their own carry.
+///
+/// A pawn touching several platforms is given one move, their carries summed.
+pub fn platform_move(
+    mut commands: Commands,
+    mut r_collision_map: ResMut<CollisionMap>,
+    r_time: Res<Time>,
+
+    mut q_platforms: Query<(Entity, &mut KinematicPlatform)>,
+    q_pawns: Query<&Pawn>,
+) {
+    let dt = r_time.delta_secs();
+    let mut carried = Vec::<(Entity, Vec2)>::new();
+    for (entity, mut platform) in &mut q_platforms {
+        let from    = platform.origin_moved;
+        let delta   = platform.origin - from;
+        let surface = platform.surface_velocity*dt;
+        platform.delta = delta;
+        platform.origin_moved    = platform.origin;
+        platform.origin_previous = from;
+
+        if delta == Vec2::ZERO && surface == Vec2::ZERO {
+            continue;
+        }
+
+        // Carried pawns are found before the entry moves, while they're still touching it
+        let [min, max] = platform.collider.bounding_box();
+        let reach = Vec2::splat(KinematicPlatform::CARRY_DISTANCE);
+        let bounds_min = from.min(platform.origin) + min - reach;
+        let bounds_max = from.max(platform.origin) + max + reach;
+
+        for entry in r_collision_map.overlap_aabb(bounds_min, bounds_max, CollisionFilter::ALL) {
+            let Some(pawn_entity) = entry.entity.filter(|&e| entry.dynamic && e != entity) else { continue; };
+            let Ok(pawn) = q_pawns.get(pawn_entity) else { continue; };
+            if !platform.filter.interacts_with(pawn.filter()) {
+                continue;
+            }
+
+            if let Some(motion) = platform.carry_motion(pawn, from, delta, surface) {
+                match carried.iter_mut().find(|v| v.0 == pawn_entity) {
+                    Some(v) => v.1 += motion,
+                    None    => carried.push((pawn_entity, motion)),
+                }
+            }
+        }
+
+        // Conveyors stay put, so they're left in place for the pawns they carry to slide along
+        if delta != Vec2::ZERO {
+            r_collision_map.remove_entity(entity);
+        }
+    }
+
+    for (pawn_entity, motion) in carried {
+        PawnMove::relative(pawn_entity, motion).do_deferred(&mut commands);
+    }
+}
+
+/// Registers platforms in the [`CollisionMap`] at their new origins, once their carried pawns have moved
+pub fn platform_register(
+    mut r_collision_map: ResMut<CollisionMap>,
+    mut l_generation: Local<u64>,
+    q_platforms: Query<(Entity, &KinematicPlatform)>,
+    mut ev_removed: RemovedComponents<KinematicPlatform>,
+) {
+    for entity in ev_removed.read() {
+        r_collision_map.remove_entity(entity);
+    }
+
+    // The map was cleared (ie. a level reload), everything needs re-registering
+    let reregister = *l_generation != r_collision_map.generation();
+    *l_generation = r_collision_map.generation();
+
+    for (entity, platform) in &q_platforms {
+        if platform.delta == Vec2::ZERO && !reregister && !r_collision_map.entity_entries(entity).is_empty() {
+            continue;
+        }
+
+        r_collision_map.remove_entity(entity);
+        if platform.sensor {
+            r_collision_map.insert_sensor(platform.origin, platform.collider, platform.filter, Some(entity));
+        } else {
+            r_collision_map.insert_dynamic(platform.origin, platform.collider, platform.filter, Some(entity));
+        }
+    }
+}
+
+/// Flags pawns left overlapping a platform once its carried pawns have been moved, and it's back in the map
+pub fn platform_detect_crush(
+    q_platforms: Query<(Entity, &KinematicPlatform)>,
+    q_pawns: Query<&Pawn>,
+    r_collision_map: Res<CollisionMap>,
+    mut ev_crush: EventWriter<PlatformCrushEvent>,
+) {
+    for (entity, platform) in &q_platforms {
+        if platform.sensor || platform.delta == Vec2::ZERO {
+            continue;
+        }
+
+        let [min, max] = platform.collider.bounding_box();
+        for entry in r_collision_map.overlap_aabb(platform.origin + min, platform.origin + max, platform.filter) {
+            let Some(pawn_entity) = entry.entity.filter(|_| entry.dynamic) else { continue; };
+            let Ok(pawn) = q_pawns.get(pawn_entity) else { continue; };
+
+            // Probe inwards from the pawn's origin to find how deep it sits within the platform
+            let shape    = pawn.collider().shrunk_by(KinematicPlatform::CRUSH_TOLERANCE);
+            let combined = ShapeCombined::between_moving_and_static(&shape, &platform.collider);
+            if !combined.contains_point(platform.origin, pawn.origin()) {
+                continue;
+            }
+
+            let direction   = (platform.origin - pawn.origin()).try_normalize().unwrap_or(Vec2::X);
+            let penetration = combined.raycast_exit(platform.origin, &RayCaster::new(pawn.origin(), -direction)).map_or(0.0, |v| v.distance);
+            ev_crush.send(PlatformCrushEvent{pawn: pawn_entity, platform: entity, penetration});
+        }
+    }
+}
+
+/// Places platforms between their last two ticks' origins, the same as the pawns riding them,
+/// see [`crate::pawn::sync_pawn_transform`]
+pub fn sync_platform_transform(
+    mut q_platforms: Query<(&mut Transform, &KinematicPlatform)>,
+    r_time: Res<Time<Fixed>>,
+    r_settings: Option<Res<PawnTransformSettings>>,
+    r_ppu: Option<Res<PixelsPerUnit>>,
+) {
+    let settings = r_settings.map_or_else(PawnTransformSettings::default, |v| *v);
+    let alpha    = r_time.overstep_fraction();
+    let ppu      = r_ppu.map(|v| *v);
+
+    q_platforms.iter_mut().for_each(|(mut t, p)| {
+        let origin = settings.render_origin(p.origin_previous, p.origin_moved, alpha, ppu);
+        t.translation = origin.extend(t.translation.z);
+    });
+}
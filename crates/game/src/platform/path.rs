@@ -0,0 +1,102 @@
+// Copyright 2025 Natalie Baker // AGPLv3 //
+
+use bevy::prelude::*;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum PlatformPathMode {
+    /// Returns to the first point after reaching the last
+    #[default]
+    Loop,
+    /// Reverses back along the path after reaching either end
+    PingPong,
+    /// Stops at the last point
+    Once,
+}
+
+/// Moves a [`super::KinematicPlatform`] through a series of points at a constant speed
+#[derive(Debug, Clone, Component)]
+pub struct PlatformPath {
+    points:  Vec<Vec2>,
+    speed:   f32,
+    mode:    PlatformPathMode,
+    target:  usize,
+    reverse: bool,
+}
+
+impl PlatformPath {
+
+    #[must_use]
+    pub fn new(points: impl Into<Vec<Vec2>>, speed: f32, mode: PlatformPathMode) -> Self {
+        Self {
+            points: points.into(),
+            speed,
+            mode,
+            target: 0,
+            reverse: false,
+        }
+    }
+
+    #[must_use]
+    pub fn points(&self) -> &[Vec2] {
+        &self.points
+    }
+
+    #[must_use]
+    pub const fn speed(&self) -> f32 {
+        self.speed
+    }
+
+    pub const fn set_speed(&mut self, v: f32) {
+        self.speed = v;
+    }
+
+    #[must_use]
+    pub const fn mode(&self) -> PlatformPathMode {
+        self.mode
+    }
+
+    /// True once a [`PlatformPathMode::Once`] path has reached its last point
+    #[must_use]
+    pub fn finished(&self) -> bool {
+        self.mode == PlatformPathMode::Once && self.target >= self.points.len()
+    }
+
+    /// Returns the position after travelling the given distance from the origin along the path
+    pub fn advance(&mut self, mut origin: Vec2, mut distance: f32) -> Vec2 {
+        // Bounded, so a degenerate path of coincident points can't spin forever
+        for _ in 0..=self.points.len() {
+            let Some(&target) = self.points.get(self.target) else { break; };
+
+            let delta  = target - origin;
+            let length = delta.length();
+            if length > distance {
+                return origin + delta*(distance/length);
+            }
+
+            origin    = target;
+            distance -= length;
+            self.next_target();
+        }
+        origin
+    }
+
+    fn next_target(&mut self) {
+        let count = self.points.len();
+        match self.mode {
+            PlatformPathMode::Loop => self.target = (self.target + 1) % count,
+            PlatformPathMode::Once => self.target += 1,
+            PlatformPathMode::PingPong => {
+                if count < 2 {
+                    return;
+                }
+                if self.reverse && self.target == 0 {
+                    self.reverse = false;
+                } else if !self.reverse && self.target == count - 1 {
+                    self.reverse = true;
+                }
+                self.target = if self.reverse { self.target - 1 } else { self.target + 1 };
+            },
+        }
+    }
+
+}
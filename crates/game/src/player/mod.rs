@@ -0,0 +1,201 @@
+// Copyright 2025 Natalie Baker // AGPLv3 //
+
+use bevy::{prelude::*, window::PrimaryWindow};
+
+use crate::{collision::CollisionMap, input::{InputAction, InputActions}, nav::{NavGrids, PawnPath}, pawn::{Pawn, PawnController, PawnMove, PawnPlatformer}};
+
+mod slot;
+pub use slot::*;
+
+#[derive(Debug, Clone, Copy, Component)]
+pub struct PawnPlayer {
+    pub action_movement: Option<PawnMove>,
+
+    pub move_speed_min: f32,
+    pub move_speed_max: f32
+}
+
+impl PawnPlayer {
+    pub fn set_move_target_and_retain_max_speed(&mut self, movement: PawnMove) {
+        self.action_movement = self.action_movement.map_or_else(
+            || Some(movement), 
+            |existing| Some(movement.with_speed(movement.speed.max(existing.speed)))
+        );
+    }
+}
+
+#[derive(Component)]
+pub struct CameraPlayer;
+
+/// Moves players by their move and sprint actions, through their [`PawnController`] when they
+/// have one. The jump action jumps for those with a [`PawnPlatformer`].
+pub fn player_move_actions(
+    mut commands: Commands,
+    mut q_players: Query<(Entity, &mut PawnPlayer, &InputActions, Option<&mut PawnController>, Option<&mut PawnPlatformer>, Has<PawnPath>)>,
+    r_time: Res<Time>,
+) {
+    q_players.iter_mut().for_each(|(entity, mut player, actions, controller, platformer, has_path)| {
+        if let Some(mut platformer) = platformer {
+            platformer.set_jump_held(actions.pressed(InputAction::Jump));
+        }
+
+        // Sticks move slower the less they're pushed, keys always at full tilt
+        let movement   = actions.movement();
+        let factor     = actions.value(InputAction::Sprint);
+        let move_speed = f32::lerp(player.move_speed_min, player.move_speed_max, factor);
+
+        // Controllers need telling when to stop, so they're given input every tick
+        let controlled = controller.is_some();
+        if let Some(mut controller) = controller {
+            let scale = if controller.max_speed() > 0.0 { move_speed/controller.max_speed() } else { 0.0 };
+            controller.set_input(movement*scale);
+        }
+
+        if movement == Vec2::ZERO {
+            return;
+        }
+
+        // Steering by hand abandons any click-to-move path
+        if has_path {
+            commands.entity(entity).remove::<PawnPath>();
+        }
+
+        if !controlled {
+            let distance = r_time.delta_secs()*move_speed*movement.length();
+            player.set_move_target_and_retain_max_speed(PawnMove::relative(entity, movement.normalize()).with_speed(distance));
+        }
+    }); 
+}
+
+/// Walks players towards the cursor, pathing around walls when it isn't in plain sight. The
+/// cursor is read through the camera of the player's slot, and only while it's over that viewport.
+pub fn player_move_mouse(
+    mut commands: Commands,
+    mut q_players: Query<(Entity, &Pawn, &PawnPlayer, &InputActions, Option<&PlayerSlot>, Option<&mut PawnPath>)>,
+
+    q_window: Query<&Window, With<PrimaryWindow>>,
+    q_camera: Query<(&Camera, &GlobalTransform, Option<&PlayerSlot>), With<CameraPlayer>>,
+
+    r_nav_grids: Res<NavGrids>,
+    r_collision_map: Res<CollisionMap>,
+) {
+    let Ok(window) = q_window.get_single() else { return; };
+    q_players.iter_mut().for_each(|(entity, pawn, player, actions, slot, path)| {
+        if !actions.device().reads_keyboard_mouse() || !actions.pressed(InputAction::MoveTo) {
+            return;
+        }
+
+        let Some((camera, camera_transform, _)) = q_camera.iter().find(|v| v.2 == slot) else { return; };
+        let Some(target) = try_get_cursor_world_position(window, camera, camera_transform) else { return; };
+
+        let grid   = r_nav_grids.for_pawn(pawn);
+        let factor = reverse_lerp(target.distance(pawn.origin()), 0.2, 2.0);
+        let move_speed = f32::lerp(player.move_speed_min, player.move_speed_max, factor);
+
+        // Only search again once the cursor moves to another cell
+        if let Some(mut path) = path.filter(|p| p.goal().is_some_and(|goal| grid.cell_of(goal) == grid.cell_of(target))) {
+            path.set_speed(move_speed);
+            return;
+        }
+
+        let path = PawnPath::find(grid, &r_collision_map, pawn.origin(), target, move_speed)
+            .unwrap_or_else(|| PawnPath::new(vec![target], move_speed));
+        commands.entity(entity).insert(path);
+    }); 
+}
+
+pub fn player_move_apply(
+    mut commands: Commands,
+    mut q_players: Query<&mut PawnPlayer>,
+) {
+    q_players.iter_mut().for_each(|mut player| {
+        if let Some(action_movement) = core::mem::take(&mut player.action_movement) {
+            action_movement.do_deferred(&mut commands);
+        }
+    });
+}
+
+/// Where the cursor points in the world through the camera, or None when it's outside the camera's viewport
+#[must_use]
+pub fn try_get_cursor_world_position(
+    window: &Window,
+    camera: &Camera,
+    camera_transform: &GlobalTransform
+) -> Option<Vec2> {
+    // Window coordinates, the camera removes its viewport's offset itself
+    let viewport = camera.logical_viewport_rect()?;
+    window.cursor_position()
+        .filter(|&cursor| viewport.contains(cursor))
+        .and_then(|cursor| camera.viewport_to_world(camera_transform, cursor).ok())
+        .map(|ray| ray.origin.truncate())
+}
+
+#[must_use]
+pub fn reverse_lerp(value: f32, start: f32, end: f32) -> f32 {
+    ((value - start)/(end - start)).clamp(0.0, 1.0)
+}
+#[cfg(test)]
+mod tests {
+    use bevy::{
+        ecs::system::RunSystemOnce,
+        prelude::*,
+        render::camera::{camera_system, ManualTextureViews, Viewport},
+        window::{PrimaryWindow, WindowCreated, WindowResized, WindowResolution, WindowScaleFactorChanged},
+    };
+
+    use super::try_get_cursor_world_position;
+
+    /// A window split in two, with the camera on the right half
+    fn split_screen(cursor: Vec2) -> App {
+        let mut app = App::new();
+        app
+            .add_event::<WindowResized>()
+            .add_event::<WindowCreated>()
+            .add_event::<WindowScaleFactorChanged>()
+            .add_event::<AssetEvent<Image>>()
+            .init_resource::<Assets<Image>>()
+            .init_resource::<ManualTextureViews>();
+
+        let mut window = Window{resolution: WindowResolution::new(200.0, 100.0).with_scale_factor_override(1.0), ..default()};
+        window.set_cursor_position(Some(cursor));
+        app.world_mut().spawn((window, PrimaryWindow));
+        app.world_mut().spawn((
+            Camera{
+                viewport: Some(Viewport{physical_position: UVec2::new(100, 0), physical_size: UVec2::new(100, 100), ..default()}),
+                ..default()
+            },
+            Projection::Orthographic(OrthographicProjection::default_2d()),
+            GlobalTransform::IDENTITY,
+        ));
+
+        app.world_mut().run_system_once(camera_system).unwrap();
+        app
+    }
+
+    fn cursor_world_position(app: &mut App) -> Option<Vec2> {
+        let world = app.world_mut();
+        let window = world.query_filtered::<&Window, With<PrimaryWindow>>().get_single(world).unwrap().clone();
+        let (camera, transform) = world.query::<(&Camera, &GlobalTransform)>().get_single(world).unwrap();
+        try_get_cursor_world_position(&window, camera, transform)
+    }
+
+    #[test]
+    fn offset_viewport_center_is_the_camera_origin() {
+        let mut app = split_screen(Vec2::new(150.0, 50.0));
+        let position = cursor_world_position(&mut app).unwrap();
+        assert!(position.length() < 1e-3, "{position}");
+    }
+
+    #[test]
+    fn offset_viewport_corner_is_half_its_size_from_the_origin() {
+        let mut app = split_screen(Vec2::new(110.0, 10.0));
+        let position = cursor_world_position(&mut app).unwrap();
+        assert!(position.distance(Vec2::new(-40.0, 40.0)) < 1e-3, "{position}");
+    }
+
+    #[test]
+    fn cursor_outside_viewport_points_nowhere() {
+        let mut app = split_screen(Vec2::new(50.0, 50.0));
+        assert!(cursor_world_position(&mut app).is_none());
+    }
+}
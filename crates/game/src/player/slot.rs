@@ -0,0 +1,175 @@
+// Copyright 2025 Natalie Baker // AGPLv3 //
+
+use bevy::{prelude::*, render::camera::Viewport, window::PrimaryWindow};
+
+use crate::{input::{input_actions_update, InputActions, InputDevice}, scale::apply_pixel_scale};
+
+/// Local multiplayer: hands gamepads out to waiting slots, or joins a new player with them, points
+/// each player's actions at their slot's device, and splits the window between the players' cameras
+pub struct PluginPlayerSlots;
+
+impl Plugin for PluginPlayerSlots {
+    fn build(&self, app: &mut App) {
+        app
+            .add_event::<PlayerJoined>()
+            .init_resource::<PlayerSlots>()
+            .add_systems(PreUpdate, (player_slots_assign_gamepads, player_slots_sync).chain().before(input_actions_update))
+            .add_systems(PostUpdate, player_viewport_layout.before(apply_pixel_scale));
+    }
+}
+
+/// The local players, by the input device each plays with. Defaults to a single player on the
+/// keyboard and mouse, gamepads join as players of their own.
+#[derive(Debug, Clone, Resource)]
+pub struct PlayerSlots {
+    devices: Vec<InputDevice>,
+}
+
+impl Default for PlayerSlots {
+    fn default() -> Self {
+        Self::new([InputDevice::KeyboardMouse])
+    }
+}
+
+impl PlayerSlots {
+
+    #[must_use]
+    pub fn new(devices: impl IntoIterator<Item = InputDevice>) -> Self {
+        Self { devices: devices.into_iter().collect() }
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.devices.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.devices.is_empty()
+    }
+
+    #[must_use]
+    pub fn device(&self, slot: usize) -> Option<InputDevice> {
+        self.devices.get(slot).copied()
+    }
+
+    /// Adds a slot, returning its index. Use [`InputDevice::None`] to wait for the next gamepad connected.
+    pub fn add(&mut self, device: InputDevice) -> usize {
+        self.devices.push(device);
+        self.devices.len() - 1
+    }
+
+    pub fn assign(&mut self, slot: usize, device: InputDevice) {
+        if let Some(v) = self.devices.get_mut(slot) {
+            *v = device;
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (usize, InputDevice)> + '_ {
+        self.devices.iter().copied().enumerate()
+    }
+
+}
+
+/// Sent when a gamepad connects with no slot waiting for it, and a slot was added for it. The new
+/// player still needs a pawn spawning.
+#[derive(Debug, Clone, Copy, Event)]
+pub struct PlayerJoined {
+    pub slot: usize,
+}
+
+/// Ties a player's pawn, and its camera, to one of the [`PlayerSlots`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Component)]
+pub struct PlayerSlot(pub usize);
+
+/// Gives newly connected gamepads to slots waiting on one, or a slot of their own when none are,
+/// and frees slots whose gamepad disconnects. Slots reading [`InputDevice::Any`] are moved to the
+/// keyboard and mouse once a gamepad has a player, so it doesn't drive two.
+pub fn player_slots_assign_gamepads(
+    mut r_slots: ResMut<PlayerSlots>,
+    q_added: Query<Entity, Added<Gamepad>>,
+    mut ev_removed: RemovedComponents<Gamepad>,
+    mut ev_joined: EventWriter<PlayerJoined>,
+) {
+    for gamepad in ev_removed.read() {
+        let slots = r_slots.iter().filter(|v| v.1 == InputDevice::Gamepad(gamepad)).map(|v| v.0).collect::<Vec<_>>();
+        slots.into_iter().for_each(|slot| r_slots.assign(slot, InputDevice::None));
+    }
+
+    for gamepad in &q_added {
+        if r_slots.iter().any(|v| v.1 == InputDevice::Gamepad(gamepad)) {
+            continue;
+        }
+
+        let waiting = r_slots.iter().find(|v| v.1 == InputDevice::None).map(|v| v.0);
+        if let Some(slot) = waiting {
+            r_slots.assign(slot, InputDevice::Gamepad(gamepad));
+        } else {
+            let slot = r_slots.add(InputDevice::Gamepad(gamepad));
+            ev_joined.send(PlayerJoined{slot});
+        }
+
+        let shared = r_slots.iter().filter(|v| v.1 == InputDevice::Any).map(|v| v.0).collect::<Vec<_>>();
+        shared.into_iter().for_each(|slot| r_slots.assign(slot, InputDevice::KeyboardMouse));
+    }
+}
+
+pub fn player_slots_sync(
+    mut q_players: Query<(&PlayerSlot, &mut InputActions)>,
+    r_slots: Res<PlayerSlots>,
+) {
+    for (slot, mut actions) in &mut q_players {
+        let device = r_slots.device(slot.0).unwrap_or(InputDevice::None);
+        if actions.device() != device {
+            actions.set_device(device);
+        }
+    }
+}
+
+/// Splits the window into a grid of viewports, one per slot with a camera, in slot order
+pub fn player_viewport_layout(
+    mut q_cameras: Query<(&mut Camera, &PlayerSlot)>,
+    q_window: Query<&Window, With<PrimaryWindow>>,
+) {
+    let Ok(window) = q_window.get_single() else { return; };
+
+    let mut slots = q_cameras.iter().map(|v| v.1.0).collect::<Vec<_>>();
+    slots.sort_unstable();
+    slots.dedup();
+
+    for (mut camera, slot) in &mut q_cameras {
+        let rank = slots.binary_search(&slot.0).unwrap_or_default();
+        let viewport = split_screen_viewport(window.physical_size(), slots.len(), rank);
+
+        // Only touch the camera when the layout changes, ie. on resize or a player joining
+        let bounds = |v: &Viewport| (v.physical_position, v.physical_size);
+        if camera.viewport.as_ref().map(bounds) != viewport.as_ref().map(bounds) {
+            camera.viewport = viewport;
+        }
+
+        let order = slot.0 as isize;
+        if camera.order != order {
+            camera.order = order;
+        }
+    }
+}
+
+/// The viewport of the player at `rank` when the window is split between `count` players, as
+/// evenly sized columns then rows. None when there's only one player, who gets the whole window.
+#[must_use]
+pub fn split_screen_viewport(window_size: UVec2, count: usize, rank: usize) -> Option<Viewport> {
+    if count <= 1 {
+        return None;
+    }
+
+    let columns = (count as f32).sqrt().ceil() as u32;
+    let rows    = (count as u32).div_ceil(columns);
+    let cell    = (window_size/UVec2::new(columns, rows)).max(UVec2::ONE);
+    let rank    = rank as u32;
+
+    Some(Viewport {
+        physical_position: cell*UVec2::new(rank % columns, rank / columns),
+        physical_size: cell,
+        ..default()
+    })
+}
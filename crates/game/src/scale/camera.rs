@@ -4,6 +4,9 @@ use bevy::{prelude::*, render::camera::ScalingMode};
 
 use super::{ppu_scale_to_fit, PixelsPerUnit};
 
+/// Scales an orthographic camera to a whole number of screen pixels per texel, showing at least
+/// the target size. Sized to the camera's viewport when it has one, so each split-screen player
+/// stays pixel perfect, and to its render target otherwise.
 #[derive(Debug, Clone, Copy, Component)]
 pub struct CameraPixelScaler {
     pub size_target_units: Vec2,
@@ -0,0 +1,158 @@
+// Copyright 2025 Natalie Baker // AGPLv3 //
+
+use core::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::{gizmos::{render_shape_debug_data_3d, DebugDrawOptions}, motion::MotionFrame, ray::RayIntersection, shape::{ShapeDebug, ShapeDebugData}};
+
+pub struct PluginDebugDraw;
+
+impl Plugin for PluginDebugDraw {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<DebugDrawQueue>()
+            .add_systems(PostUpdate, debug_draw_queue_render);
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum DebugDrawLifetime {
+    Frames(u32),
+    Duration(Duration),
+}
+
+impl DebugDrawLifetime {
+
+    #[must_use]
+    pub const fn single_frame() -> Self {
+        Self::Frames(1)
+    }
+
+    /// Negative or NaN seconds last no time at all, drawing for a single frame, and those too
+    /// large for a [`Duration`] last forever
+    #[must_use]
+    pub fn seconds(seconds: f32) -> Self {
+        Self::Duration(Duration::try_from_secs_f32(seconds.max(0.0)).unwrap_or(Duration::MAX))
+    }
+
+    #[must_use]
+    fn advanced_by(self, delta: Duration) -> Option<Self> {
+        match self {
+            Self::Frames(v)   => v.checked_sub(1).filter(|&v| v > 0).map(Self::Frames),
+            Self::Duration(v) => v.checked_sub(delta).filter(|v| !v.is_zero()).map(Self::Duration),
+        }
+    }
+
+}
+
+pub enum DebugDrawItem {
+    Shape{
+        origin: Vec2,
+        data:   ShapeDebugData,
+    },
+    Ray{
+        origin:    Vec2,
+        direction: Vec2,
+        length:    f32,
+    },
+    Intersection(RayIntersection),
+    Motion(MotionFrame),
+}
+
+struct DebugDrawEntry {
+    item:     DebugDrawItem,
+    options:  DebugDrawOptions,
+    lifetime: DebugDrawLifetime,
+}
+
+#[derive(Default, Resource)]
+pub struct DebugDrawQueue {
+    entries: Vec<DebugDrawEntry>,
+}
+
+impl DebugDrawQueue {
+
+    pub fn push(&mut self, item: DebugDrawItem, options: DebugDrawOptions, lifetime: DebugDrawLifetime) {
+        self.entries.push(DebugDrawEntry{item, options, lifetime});
+    }
+
+    pub fn shape(&mut self, origin: Vec2, shape: &impl ShapeDebug, options: DebugDrawOptions, lifetime: DebugDrawLifetime) {
+        self.push(DebugDrawItem::Shape{origin, data: shape.get_debug_shape_data()}, options, lifetime);
+    }
+
+    pub fn ray(&mut self, origin: Vec2, direction: Vec2, length: f32, options: DebugDrawOptions, lifetime: DebugDrawLifetime) {
+        self.push(DebugDrawItem::Ray{origin, direction, length}, options, lifetime);
+    }
+
+    pub fn intersection(&mut self, hit: RayIntersection, options: DebugDrawOptions, lifetime: DebugDrawLifetime) {
+        self.push(DebugDrawItem::Intersection(hit), options, lifetime);
+    }
+
+    pub fn motion(&mut self, motion: MotionFrame, options: DebugDrawOptions, lifetime: DebugDrawLifetime) {
+        self.push(DebugDrawItem::Motion(motion), options, lifetime);
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+}
+
+pub fn debug_draw_queue_render(
+    mut gizmos: Gizmos,
+    mut r_queue: ResMut<DebugDrawQueue>,
+    r_time: Res<Time>,
+) {
+    let delta = r_time.delta();
+    r_queue.entries.retain_mut(|entry| {
+        render_debug_draw_item(&mut gizmos, &entry.item, entry.options);
+        if let Some(lifetime) = entry.lifetime.advanced_by(delta) {
+            entry.lifetime = lifetime;
+            true
+        } else {
+            false
+        }
+    });
+}
+
+pub fn render_debug_draw_item(gizmos: &mut Gizmos, item: &DebugDrawItem, options: DebugDrawOptions) {
+    let depth  = options.depth;
+    let colour = options.colour;
+    match item {
+        DebugDrawItem::Shape { origin, data } => {
+            render_shape_debug_data_3d(gizmos, *origin, data, options);
+        },
+        DebugDrawItem::Ray { origin, direction, length } => {
+            gizmos.line(origin.extend(depth), (*origin + *direction * *length).extend(depth), colour);
+            gizmos.circle(origin.extend(depth), 0.1, colour);
+        },
+        DebugDrawItem::Intersection(hit) => {
+            gizmos.circle(hit.point.extend(depth), 0.1, colour);
+            gizmos.line(hit.point.extend(depth), (hit.point + hit.normal).extend(depth), colour);
+        },
+        DebugDrawItem::Motion(motion) => {
+            let data  = motion.collider().get_debug_shape_data();
+            let start = motion.position_start();
+            let end   = motion.position_end();
+            render_shape_debug_data_3d(gizmos, start, &data, options);
+            render_shape_debug_data_3d(gizmos, end,   &data, options);
+            gizmos.line(start.extend(depth), end.extend(depth), colour);
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::time::Duration;
+
+    use super::DebugDrawLifetime;
+
+    #[test]
+    fn seconds_out_of_range_dont_panic() {
+        for (seconds, expected) in [(-1.0, Duration::ZERO), (f32::NAN, Duration::ZERO), (f32::INFINITY, Duration::MAX), (1e30, Duration::MAX), (0.5, Duration::from_millis(500))] {
+            let DebugDrawLifetime::Duration(v) = DebugDrawLifetime::seconds(seconds) else { panic!("{seconds} isn't a duration") };
+            assert_eq!(v, expected, "{seconds}");
+        }
+    }
+}
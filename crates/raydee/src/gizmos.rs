@@ -2,7 +2,7 @@
 
 use bevy::prelude::*;
 
-use crate::shape::ShapeDebugData;
+use crate::{motion::{MotionTestOutcome, MotionTrace}, shape::{ShapeDebug, ShapeDebugData}};
 
 #[derive(Debug, Clone, Copy)]
 pub struct DebugDrawOptions {
@@ -95,6 +95,32 @@ pub fn render_shape_debug_data_3d(gizmos: &mut Gizmos, origin: Vec2, data: &Shap
     );
 }
 
+pub fn render_motion_trace_2d(gizmos: &mut Gizmos, trace: &MotionTrace, options: DebugDrawOptions) {
+    let colour_rejected = options.colour.with_alpha(0.25);
+    let colour_accepted = Color::linear_rgb(1.0, 0.0, 0.0);
+
+    for iteration in trace.iterations() {
+        let motion = iteration.motion;
+        let start  = motion.position_start();
+        let end    = motion.position_end();
+        render_shape_debug_data_2d(gizmos, start, &motion.collider().get_debug_shape_data(), options);
+        gizmos.line_2d(start, end, options.colour);
+
+        for test in &iteration.tests {
+            let candidate = &iteration.candidates[test.candidate];
+            let colour = if test.result.outcome == MotionTestOutcome::Accepted { colour_accepted } else { colour_rejected };
+            render_shape_debug_data_2d(gizmos, candidate.origin, &candidate.collider.get_debug_shape_data(), options.with_colour(colour));
+            if let Some(hit) = test.result.hit {
+                gizmos.line_2d(hit.point, hit.point + hit.normal, colour);
+            }
+        }
+
+        if let Some(result) = iteration.result {
+            gizmos.line_2d(result.position_start(), result.position_end(), colour_accepted);
+        }
+    }
+}
+
 pub fn render_shape_debug_data(
     gizmos: &mut Gizmos, 
     origin: Vec2, 
@@ -3,11 +3,15 @@
 mod shape;
 mod ray;
 mod gizmos;
+mod debug_draw;
+mod mesh;
 mod motion;
 
 pub mod prelude {
     pub use crate::ray::*;
     pub use crate::shape::*;
     pub use crate::gizmos::*;
+    pub use crate::debug_draw::*;
+    pub use crate::mesh::*;
     pub use crate::motion::*;
 }
\ No newline at end of file
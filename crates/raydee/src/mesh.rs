@@ -0,0 +1,131 @@
+// Copyright 2025 Natalie Baker // AGPLv3 //
+
+use bevy::{asset::RenderAssetUsages, prelude::*, render::mesh::{Indices, PrimitiveTopology}};
+
+use crate::shape::{ShapeDebug, ShapeDebugData};
+
+#[derive(Debug, Clone, Copy)]
+pub struct ShapeMeshOptions {
+    pub segments: u32,
+    pub stroke_width: f32,
+    pub depth: f32,
+}
+
+impl ShapeMeshOptions {
+
+    #[must_use]
+    pub const fn new() -> Self {
+        Self{
+            segments: 32,
+            stroke_width: 1.0,
+            depth: 0.0,
+        }
+    }
+
+    #[must_use]
+    pub const fn with_segments(self, segments: u32) -> Self {
+        Self{
+            segments,
+            ..self
+        }
+    }
+
+    #[must_use]
+    pub const fn with_stroke_width(self, stroke_width: f32) -> Self {
+        Self{
+            stroke_width,
+            ..self
+        }
+    }
+
+    #[must_use]
+    pub const fn with_depth(self, depth: f32) -> Self {
+        Self{
+            depth,
+            ..self
+        }
+    }
+}
+
+impl Default for ShapeMeshOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub trait ShapeMesh {
+    fn create_mesh_fill(&self, options: ShapeMeshOptions) -> Mesh;
+    fn create_mesh_stroke(&self, options: ShapeMeshOptions) -> Mesh;
+}
+
+impl<T: ShapeDebug + ?Sized> ShapeMesh for T {
+    fn create_mesh_fill(&self, options: ShapeMeshOptions) -> Mesh {
+        create_shape_mesh_fill(&self.get_debug_shape_data(), options)
+    }
+
+    fn create_mesh_stroke(&self, options: ShapeMeshOptions) -> Mesh {
+        create_shape_mesh_stroke(&self.get_debug_shape_data(), options)
+    }
+}
+
+#[must_use]
+pub fn create_shape_mesh_fill(data: &ShapeDebugData, options: ShapeMeshOptions) -> Mesh {
+    let outline = data.outline(options.segments);
+    let center  = outline.iter().copied().sum::<Vec2>() / (outline.len().max(1) as f32);
+
+    // Shapes are convex, so a fan around the center covers them
+    let positions: Vec<Vec2> = core::iter::once(center).chain(outline.iter().copied()).collect();
+    let indices: Vec<u32> = (0..outline.len() as u32).flat_map(|i| [
+        0,
+        1 + i,
+        1 + (i + 1) % (outline.len() as u32)
+    ]).collect();
+
+    create_mesh(&positions, indices, options.depth)
+}
+
+#[must_use]
+pub fn create_shape_mesh_stroke(data: &ShapeDebugData, options: ShapeMeshOptions) -> Mesh {
+    let outline = data.outline(options.segments);
+    let half_width = options.stroke_width * 0.5;
+    let len = outline.len();
+
+    let mut positions = Vec::with_capacity(len*2);
+    for i in 0..len {
+        let prev = outline[(i + len - 1) % len];
+        let curr = outline[i];
+        let next = outline[(i + 1) % len];
+
+        let norm_in  = -(curr - prev).normalize_or_zero().perp();
+        let norm_out = -(next - curr).normalize_or_zero().perp();
+        let norm = (norm_in + norm_out).try_normalize().unwrap_or(norm_out);
+
+        // Miter, clamped so sharp corners don't spike out
+        let offset = norm * (half_width / norm.dot(norm_out).max(0.25));
+        positions.extend([curr - offset, curr + offset]);
+    }
+
+    let indices: Vec<u32> = (0..len as u32).flat_map(|i| {
+        let inner_curr = 2*i;
+        let outer_curr = 2*i + 1;
+        let inner_next = 2*((i + 1) % (len as u32));
+        let outer_next = 2*((i + 1) % (len as u32)) + 1;
+        [
+            inner_curr, outer_curr, outer_next,
+            inner_curr, outer_next, inner_next,
+        ]
+    }).collect();
+
+    create_mesh(&positions, indices, options.depth)
+}
+
+fn create_mesh(positions: &[Vec2], indices: Vec<u32>, depth: f32) -> Mesh {
+    let (min, max) = positions.iter().fold((Vec2::MAX, Vec2::MIN), |(min, max), &v| (min.min(v), max.max(v)));
+    let size = (max - min).max(Vec2::splat(f32::EPSILON));
+
+    Mesh::new(PrimitiveTopology::TriangleList, RenderAssetUsages::default())
+        .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions.iter().map(|v| [v.x, v.y, depth]).collect::<Vec<_>>())
+        .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL,   vec![[0.0, 0.0, 1.0]; positions.len()])
+        .with_inserted_attribute(Mesh::ATTRIBUTE_UV_0,     positions.iter().map(|&v| ((v - min)/size).to_array()).collect::<Vec<_>>())
+        .with_inserted_indices(Indices::U32(indices))
+}
@@ -1,10 +1,11 @@
 // Copyright 2025 Natalie Baker // AGPLv3 //
 
 use bevy::prelude::Vec2;
+use serde::Serialize;
 
 use crate::{ray::{RayCaster, RayIntersection}, shape::ShapeMoving};
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize)]
 pub struct MotionFrame {
     collider:  ShapeMoving,
     origin:    Vec2,
@@ -5,3 +5,6 @@ pub use query::*;
 
 mod frame;
 pub use frame::*;
+
+mod trace;
+pub use trace::*;
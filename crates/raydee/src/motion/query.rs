@@ -1,16 +1,39 @@
 // Copyright 2025 Natalie Baker // AGPLv3 //
 
 use bevy::math::Vec2;
+use serde::Serialize;
 
-use crate::{ray::{RayIntersection, RayTarget}, shape::{ShapeCombined, ShapeStatic}};
+use crate::{ray::{RayIntersection, RayTarget}, shape::{ShapeCombined, ShapeMoving, ShapeStatic}};
 
 use super::MotionFrame;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum MotionTestRejection {
+    Miss,
+    BeyondClosest,
+    BehindSkin,
+    FacingAway,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum MotionTestOutcome {
+    Accepted,
+    Rejected(MotionTestRejection),
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct MotionTestResult {
+    pub hit:     Option<RayIntersection>,
+    pub outcome: MotionTestOutcome,
+}
+
+/// Finds the closest blocking hit for a motion. Each test can carry a source, ie. the collider
+/// tested against, which is remembered for the accepted hit.
 #[derive(Debug, Clone, Copy)]
-pub struct MotionQuery {
+pub struct MotionQuery<S: Copy = ()> {
     motion: MotionFrame,
     skin_distance: f32,
-    hit: Option<RayIntersection>,
+    hit: Option<(RayIntersection, S)>,
     distance_max: f32,
 }
 
@@ -18,6 +41,34 @@ impl MotionQuery {
 
     #[must_use] 
     pub fn new(motion: MotionFrame, skin_distance: f32) -> Self {
+        Self::with_source(motion, skin_distance)
+    }
+
+    pub fn test<'a>(&mut self, collider_origin: Vec2, collider_shape: impl Into<&'a ShapeStatic>) -> MotionTestResult {
+        self.test_with((), collider_origin, collider_shape)
+    }
+
+    pub fn test_combined(&mut self, collider_origin: Vec2, combined: &ShapeCombined) -> MotionTestResult {
+        self.test_combined_with((), collider_origin, combined)
+    }
+
+    #[must_use]
+    pub fn combine(collider: ShapeMoving, skin_distance: f32, collider_shape: &ShapeStatic) -> ShapeCombined {
+        ShapeCombined::between_moving_and_static(&collider.shrunk_by(skin_distance), collider_shape)
+    }
+
+    /// As [`MotionQuery::combine`], for colliders that are themselves moving
+    #[must_use]
+    pub fn combine_moving(collider: ShapeMoving, skin_distance: f32, collider_shape: &ShapeMoving) -> ShapeCombined {
+        ShapeCombined::between_moving(&collider.shrunk_by(skin_distance), collider_shape)
+    }
+
+}
+
+impl<S: Copy> MotionQuery<S> {
+
+    #[must_use] 
+    pub fn with_source(motion: MotionFrame, skin_distance: f32) -> Self {
         Self {
             motion,
             skin_distance,
@@ -26,29 +77,60 @@ impl MotionQuery {
         }
     }
 
-    pub fn test<'a>(&mut self, collider_origin: Vec2, collider_shape: impl Into<&'a ShapeStatic>) {
+    pub fn test_with<'a>(&mut self, source: S, collider_origin: Vec2, collider_shape: impl Into<&'a ShapeStatic>) -> MotionTestResult {
+        let combined = MotionQuery::combine(self.motion.collider(), self.skin_distance, collider_shape.into());
+        self.test_combined_with(source, collider_origin, &combined)
+    }
+
+    pub fn test_combined_with(&mut self, source: S, collider_origin: Vec2, combined: &ShapeCombined) -> MotionTestResult {
         let caster = self.motion.ray_caster();
-        let combined = ShapeCombined::between_moving_and_static(&self.motion.collider().shrunk_by(self.skin_distance), collider_shape.into());
-        if let Some(hit) = combined.raycast_enter(collider_origin, &caster) {
-            if hit.distance < self.distance_max && hit.distance >= -self.skin_distance && hit.normal.dot(self.motion.direction()) < 0.0 {
-                self.distance_max = hit.distance;
-                self.hit          = Some(hit);
+        let Some(hit) = combined.raycast_enter(collider_origin, &caster) else {
+            return MotionTestResult{hit: None, outcome: MotionTestOutcome::Rejected(MotionTestRejection::Miss)};
+        };
+
+        let outcome = if hit.distance < self.distance_max {
+            if hit.distance >= -self.skin_distance {
+                if hit.normal.dot(self.motion.direction()) < 0.0 {
+                    self.distance_max = hit.distance;
+                    self.hit          = Some((hit, source));
+                    MotionTestOutcome::Accepted
+                } else {
+                    MotionTestOutcome::Rejected(MotionTestRejection::FacingAway)
+                }
+            } else {
+                MotionTestOutcome::Rejected(MotionTestRejection::BehindSkin)
             }
-        }
+        } else {
+            MotionTestOutcome::Rejected(MotionTestRejection::BeyondClosest)
+        };
+
+        MotionTestResult{hit: Some(hit), outcome}
     }
 
     #[must_use]
     pub fn result(&self) -> MotionFrame {
-        if let Some(hit) = self.hit {
+        if let Some((hit, _)) = self.hit {
             self.motion.move_to_hit_and_slide(hit, self.skin_distance)
         } else {
             self.motion.move_to_end()
         }
     }
 
+    /// The closest accepted hit so far
+    #[must_use]
+    pub fn hit(&self) -> Option<RayIntersection> {
+        self.hit.map(|(v, _)| v)
+    }
+
+    /// The source of the closest accepted hit so far
+    #[must_use]
+    pub fn hit_source(&self) -> Option<S> {
+        self.hit.map(|(_, v)| v)
+    }
+
 }
 
-impl MotionQuery {
+impl<S: Copy> MotionQuery<S> {
 
     #[must_use]
     pub const fn motion(&self) -> &MotionFrame {
@@ -0,0 +1,142 @@
+// Copyright 2025 Natalie Baker // AGPLv3 //
+
+use core::fmt::{self, Display};
+
+use bevy::math::Vec2;
+use serde::Serialize;
+
+use crate::shape::ShapeStatic;
+
+use super::{MotionFrame, MotionTestOutcome, MotionTestResult};
+
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct MotionTraceCandidate {
+    pub origin:   Vec2,
+    pub collider: ShapeStatic,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct MotionTraceTest {
+    pub candidate: usize,
+    pub result:    MotionTestResult,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MotionTraceIteration {
+    pub motion:     MotionFrame,
+    pub bounds:     [Vec2; 2],
+    pub candidates: Vec<MotionTraceCandidate>,
+    pub tests:      Vec<MotionTraceTest>,
+    pub result:     Option<MotionFrame>,
+}
+
+impl MotionTraceIteration {
+
+    #[must_use]
+    pub fn slide_direction(&self) -> Option<Vec2> {
+        self.result.map(MotionFrame::direction)
+    }
+
+    pub fn accepted(&self) -> impl Iterator<Item = &MotionTraceTest> {
+        self.tests.iter().filter(|v| v.result.outcome == MotionTestOutcome::Accepted)
+    }
+
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MotionTrace {
+    iterations: Vec<MotionTraceIteration>,
+    limit_exceeded: bool,
+}
+
+impl MotionTrace {
+
+    pub fn clear(&mut self) {
+        self.iterations.clear();
+        self.limit_exceeded = false;
+    }
+
+    pub fn begin_iteration(&mut self, motion: MotionFrame, bounds: [Vec2; 2]) {
+        self.iterations.push(MotionTraceIteration{
+            motion,
+            bounds,
+            candidates: Vec::default(),
+            tests:      Vec::default(),
+            result:     None,
+        });
+    }
+
+    pub fn record_candidate(&mut self, origin: Vec2, collider: ShapeStatic) -> usize {
+        let iteration = self.current_iteration_mut();
+        iteration.candidates.push(MotionTraceCandidate{origin, collider});
+        iteration.candidates.len() - 1
+    }
+
+    pub fn record_test(&mut self, candidate: usize, result: MotionTestResult) {
+        self.current_iteration_mut().tests.push(MotionTraceTest{candidate, result});
+    }
+
+    pub fn end_iteration(&mut self, result: MotionFrame) {
+        self.current_iteration_mut().result = Some(result);
+    }
+
+    pub fn set_limit_exceeded(&mut self) {
+        self.limit_exceeded = true;
+    }
+
+    fn current_iteration_mut(&mut self) -> &mut MotionTraceIteration {
+        self.iterations.last_mut().expect("MotionTrace recorded outside of an iteration")
+    }
+
+}
+
+impl MotionTrace {
+
+    #[must_use]
+    pub fn iterations(&self) -> &[MotionTraceIteration] {
+        &self.iterations
+    }
+
+    #[must_use]
+    pub const fn limit_exceeded(&self) -> bool {
+        self.limit_exceeded
+    }
+
+    /// The trace as JSON, with colliders and outcomes written out structurally
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("MotionTrace always serializes")
+    }
+
+    pub fn write_json(&self, out: impl std::io::Write) -> serde_json::Result<()> {
+        serde_json::to_writer(out, self)
+    }
+
+}
+
+impl Display for MotionTrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "MotionTrace: {} iteration(s){}", self.iterations.len(), if self.limit_exceeded { ", limit exceeded" } else { "" })?;
+        for (i, iteration) in self.iterations.iter().enumerate() {
+            let motion = iteration.motion;
+            writeln!(
+                f, "  [{i}] from {} dir {} dist {} | bounds {} -> {} | {} candidate(s)",
+                motion.position_start(), motion.direction(), motion.distance(),
+                iteration.bounds[0], iteration.bounds[1],
+                iteration.candidates.len()
+            )?;
+            for test in &iteration.tests {
+                let candidate = &iteration.candidates[test.candidate];
+                write!(f, "      #{} at {} {:?}: {:?}", test.candidate, candidate.origin, candidate.collider, test.result.outcome)?;
+                if let Some(hit) = test.result.hit {
+                    write!(f, " (distance {} point {} normal {})", hit.distance, hit.point, hit.normal)?;
+                }
+                writeln!(f)?;
+            }
+            if let Some(result) = iteration.result {
+                writeln!(f, "      -> slide {} dist {} from {}", result.direction(), result.distance(), result.position_start())?;
+            }
+        }
+        Ok(())
+    }
+}
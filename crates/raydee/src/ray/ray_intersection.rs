@@ -1,8 +1,9 @@
 // Copyright 2023 Natalie Baker // AGPLv3 //
 
 use bevy::prelude::Vec2;
+use serde::Serialize;
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize)]
 pub struct RayIntersection {
     pub distance: f32,
     pub point:    Vec2,
@@ -14,4 +14,19 @@ pub trait RayTarget {
     fn raycast_exit(&self, origin: Vec2, ray: &RayCaster) -> Option<RayIntersection>{
         self.raycast(origin, ray).map(|[_, v]| v)
     }
+
+    /// Only valid for convex targets, where a point is inside if a ray through it enters before and exits after
+    fn contains_point(&self, origin: Vec2, point: Vec2) -> bool {
+        self.raycast(origin, &RayCaster::new_x(point, 1.0)).is_some_and(|[enter, exit]| enter.distance <= 0.0 && exit.distance >= 0.0)
+    }
+
+    /// Whether a point travelling from `from` to `to` touches the target at any time
+    fn sweep_touches(&self, origin: Vec2, from: Vec2, to: Vec2) -> bool {
+        let delta = to - from;
+        let length = delta.length();
+        if length <= 0.0 {
+            return self.contains_point(origin, from);
+        }
+        self.raycast(origin, &RayCaster::new(from, delta/length)).is_some_and(|[enter, exit]| enter.distance <= length && exit.distance >= 0.0)
+    }
 }
\ No newline at end of file
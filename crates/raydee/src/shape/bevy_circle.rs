@@ -2,7 +2,7 @@
 
 use bevy::{math::primitives::Circle, prelude::Vec2};
 
-use crate::prelude::{RayTarget, RayCaster, RayIntersection, ShapeDebug, ShapeDebugData, ShapeCommon};
+use crate::prelude::{RayTarget, RayCaster, RayIntersection, ShapeDebug, ShapeDebugData, ShapeCommon, ShapeDistance};
 
 impl ShapeCommon for Circle {
     fn bounding_box(&self) -> [Vec2; 2] {
@@ -13,6 +13,12 @@ impl ShapeCommon for Circle {
     }
 }
 
+impl ShapeDistance for Circle {
+    fn distance_to_point(&self, point: Vec2) -> f32 {
+        (point.length() - self.radius).max(0.0)
+    }
+}
+
 impl RayTarget for Circle {
     fn raycast(&self, origin: Vec2, ray: &RayCaster) -> Option<[RayIntersection; 2]> {
         ray.test_circle(origin, self.radius)
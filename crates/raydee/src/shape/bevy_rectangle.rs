@@ -2,7 +2,7 @@
 
 use bevy::{math::primitives::Rectangle, prelude::Vec2};
 
-use crate::prelude::{RayTarget, RayCaster, RayIntersection, ShapeDebug, ShapeDebugData, ShapeCommon};
+use crate::prelude::{RayTarget, RayCaster, RayIntersection, ShapeDebug, ShapeDebugData, ShapeCommon, ShapeDistance, distance_to_rect};
 
 impl ShapeCommon for Rectangle {
     fn bounding_box(&self) -> [Vec2; 2] {
@@ -10,6 +10,12 @@ impl ShapeCommon for Rectangle {
     }
 }
 
+impl ShapeDistance for Rectangle {
+    fn distance_to_point(&self, point: Vec2) -> f32 {
+        distance_to_rect(self.half_size, point)
+    }
+}
+
 impl RayTarget for Rectangle {
     fn raycast(&self, origin: Vec2, ray: &RayCaster) -> Option<[RayIntersection; 2]> {
         ray.test_rect(origin, self.half_size)
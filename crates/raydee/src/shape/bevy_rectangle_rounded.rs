@@ -1,10 +1,11 @@
 // Copyright 2023 Natalie Baker // AGPLv3 //
 
 use bevy::{math::primitives::Rectangle, prelude::Vec2};
+use serde::Serialize;
 
-use crate::prelude::{RayTarget, RayCaster, RayIntersection, ShapeDebugData, ShapeDebug, ShapeCommon};
+use crate::prelude::{RayTarget, RayCaster, RayIntersection, ShapeDebugData, ShapeDebug, ShapeCommon, ShapeDistance, distance_to_rect};
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize)]
 pub struct RectangleRounded {
     pub inner:  Rectangle,
     pub radius: f32,
@@ -24,6 +25,12 @@ impl ShapeCommon for RectangleRounded {
     }
 }
 
+impl ShapeDistance for RectangleRounded {
+    fn distance_to_point(&self, point: Vec2) -> f32 {
+        (distance_to_rect(self.inner.half_size, point) - self.radius).max(0.0)
+    }
+}
+
 impl RayTarget for RectangleRounded {
     fn raycast(&self, origin: Vec2, ray: &RayCaster) -> Option<[RayIntersection; 2]> {
         ray.test_rect_rounded(origin, self.inner.half_size, self.radius)
@@ -1,10 +1,11 @@
 // Copyright 2023 Natalie Baker // AGPLv3 //
 
 use bevy::prelude::Vec2;
+use serde::Serialize;
 
-use crate::prelude::{RayTarget, RayCaster, RayIntersection, ShapeDebug, ShapeDebugData, ShapeCommon};
+use crate::prelude::{RayTarget, RayCaster, RayIntersection, ShapeDebug, ShapeDebugData, ShapeCommon, ShapeDistance, distance_to_rect};
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize)]
 pub struct BoxOriented {
     pub size:      Vec2,
     pub direction: Vec2,
@@ -26,6 +27,14 @@ impl ShapeCommon for BoxOriented {
     }
 }
 
+impl ShapeDistance for BoxOriented {
+    fn distance_to_point(&self, point: Vec2) -> f32 {
+        // Rotate the point into the box's frame, where it's an axis aligned rectangle
+        let local = point.rotate(Vec2::new(self.direction.x, -self.direction.y));
+        distance_to_rect(self.size, local)
+    }
+}
+
 impl RayTarget for BoxOriented {
     fn raycast(&self, origin:Vec2, ray: &RayCaster) -> Option<[RayIntersection; 2]> {
 
@@ -76,3 +85,21 @@ impl ShapeDebug for BoxOriented {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::Vec2;
+
+    use crate::prelude::ShapeDistance;
+
+    use super::BoxOriented;
+
+    #[test]
+    fn distance_to_point_follows_rotation() {
+        // A 2x1 box turned a quarter, so it's 1 wide and 2 tall
+        let shape = BoxOriented::new(Vec2::new(1.0, 0.5), Vec2::Y);
+        assert!(shape.distance_to_point(Vec2::new(0.0, 0.9)) <= 0.0);
+        assert!((shape.distance_to_point(Vec2::new(0.0, 2.0)) - 1.0).abs() < 1e-5);
+        assert!((shape.distance_to_point(Vec2::new(1.5, 0.0)) - 1.0).abs() < 1e-5);
+    }
+}
@@ -2,7 +2,7 @@
 
 use bevy::prelude::Vec2;
 
-use crate::prelude::{RayTarget, RayCaster, RayIntersection, ShapeDebug, ShapeDebugData, get_polygon_data_for_oriented_rect_rected, PolygonSmall, ShapeCommon};
+use crate::prelude::{RayTarget, RayCaster, RayIntersection, ShapeDebug, ShapeDebugData, get_polygon_data_for_oriented_rect_rected, PolygonSmall, ShapeCommon, ShapeDistance};
 
 #[derive(Debug, Clone, Copy)]
 pub struct BoxOrientedBoxy(PolygonSmall);
@@ -20,6 +20,12 @@ impl ShapeCommon for BoxOrientedBoxy {
     }
 }
 
+impl ShapeDistance for BoxOrientedBoxy {
+    fn distance_to_point(&self, point: Vec2) -> f32 {
+        self.0.distance_to_point(point)
+    }
+}
+
 impl RayTarget for BoxOrientedBoxy {
     fn raycast(&self, origin: Vec2, ray: &RayCaster) -> Option<[RayIntersection; 2]> {
         self.0.raycast(origin, ray)
@@ -2,7 +2,7 @@
 
 use bevy::prelude::Vec2;
 
-use crate::prelude::{RayTarget, RayCaster, RayIntersection, ShapeDebug, ShapeDebugData, PolygonSmallRound, ShapeCommon};
+use crate::prelude::{RayTarget, RayCaster, RayIntersection, ShapeDebug, ShapeDebugData, PolygonSmallRound, ShapeCommon, ShapeDistance};
 use super::get_polygon_data_for_oriented_rect_rected;
 
 #[derive(Debug, Clone, Copy)]
@@ -21,6 +21,12 @@ impl ShapeCommon for BoxOrientedBoxyRound {
     }
 }
 
+impl ShapeDistance for BoxOrientedBoxyRound {
+    fn distance_to_point(&self, point: Vec2) -> f32 {
+        self.0.distance_to_point(point)
+    }
+}
+
 impl RayTarget for BoxOrientedBoxyRound {
     fn raycast(&self, origin: Vec2, ray: &RayCaster) -> Option<[RayIntersection; 2]> {
         self.0.raycast(origin, ray)
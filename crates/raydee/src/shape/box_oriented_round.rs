@@ -1,10 +1,11 @@
 // Copyright 2023 Natalie Baker // AGPLv3 //
 
 use bevy::prelude::Vec2;
+use serde::Serialize;
 
-use crate::prelude::{RayTarget, RayCaster, RayIntersection, ShapeDebug, ShapeDebugData, ShapeCommon};
+use crate::prelude::{RayTarget, RayCaster, RayIntersection, ShapeDebug, ShapeDebugData, ShapeCommon, ShapeDistance, distance_to_rect};
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize)]
 pub struct BoxOrientedRound {
     pub size:      Vec2,
     pub direction: Vec2,
@@ -30,6 +31,13 @@ impl ShapeCommon for BoxOrientedRound {
     }
 }
 
+impl ShapeDistance for BoxOrientedRound {
+    fn distance_to_point(&self, point: Vec2) -> f32 {
+        let local = point.rotate(Vec2::new(self.direction.x, -self.direction.y));
+        (distance_to_rect(self.size, local) - self.radius).max(0.0)
+    }
+}
+
 impl RayTarget for BoxOrientedRound {
     fn raycast(&self, origin: Vec2, ray: &RayCaster) -> Option<[RayIntersection; 2]> {
         let points = [
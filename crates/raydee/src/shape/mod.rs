@@ -63,6 +63,9 @@ pub use shape_combined::*;
 mod shape_common;
 pub use shape_common::*;
 
+mod shape_distance;
+pub use shape_distance::*;
+
 mod shape_debug;
 pub use shape_debug::*;
 
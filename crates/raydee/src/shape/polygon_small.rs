@@ -4,7 +4,7 @@ use bevy::prelude::Vec2;
 
 use tinyvec::ArrayVec;
 
-use crate::prelude::{RayTarget, ShapeDebug, RayCaster, RayIntersection, ShapeDebugData, ShapeCommon};
+use crate::prelude::{RayTarget, ShapeDebug, RayCaster, RayIntersection, ShapeDebugData, ShapeCommon, ShapeDistance, distance_to_polygon};
 
 pub const POLYGON_SMALL_CAPACITY: usize = 8;
 
@@ -59,6 +59,12 @@ impl ShapeCommon for PolygonSmall {
     }
 }
 
+impl ShapeDistance for PolygonSmall {
+    fn distance_to_point(&self, point: Vec2) -> f32 {
+        distance_to_polygon(&self.points, &self.normals, point)
+    }
+}
+
 impl RayTarget for PolygonSmall {
     fn raycast(&self, origin: Vec2, ray: &RayCaster) -> Option<[RayIntersection; 2]> {
         ray.test_polygon(origin, &self.points, &self.normals, &self.lengths)
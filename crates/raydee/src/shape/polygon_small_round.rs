@@ -2,7 +2,7 @@
 
 use bevy::prelude::Vec2;
 
-use crate::prelude::{RayTarget, ShapeDebug, RayCaster, RayIntersection, ShapeDebugData, PolygonSmall, ShapeCommon};
+use crate::prelude::{RayTarget, ShapeDebug, RayCaster, RayIntersection, ShapeDebugData, PolygonSmall, ShapeCommon, ShapeDistance};
 
 #[derive(Debug, Clone, Copy)]
 pub struct PolygonSmallRound {
@@ -31,6 +31,12 @@ impl ShapeCommon for PolygonSmallRound {
     }
 }
 
+impl ShapeDistance for PolygonSmallRound {
+    fn distance_to_point(&self, point: Vec2) -> f32 {
+        (self.inner.distance_to_point(point) - self.radius).max(0.0)
+    }
+}
+
 impl RayTarget for PolygonSmallRound {
     fn raycast(&self, origin: Vec2, ray: &RayCaster) -> Option<[RayIntersection; 2]> {
         ray.test_polygon_rounded(origin, &self.inner.points, &self.inner.normals, &self.inner.lengths, self.radius)
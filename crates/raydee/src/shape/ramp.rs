@@ -1,10 +1,11 @@
 // Copyright 2023 Natalie Baker // AGPLv3 //
 
 use bevy::prelude::Vec2;
+use serde::Serialize;
 
-use crate::prelude::{RayTarget, RayCaster, RayIntersection, ShapeDebug, ShapeDebugData, get_polygon_data_for_ramp, ShapeCommon};
+use crate::prelude::{RayTarget, RayCaster, RayIntersection, ShapeDebug, ShapeDebugData, get_polygon_data_for_ramp, ShapeCommon, ShapeDistance, distance_to_polygon};
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize)]
 pub struct Ramp {
     pub direction: Vec2,
     pub length:    f32,
@@ -49,6 +50,13 @@ impl ShapeCommon for Ramp {
     }
 }
 
+impl ShapeDistance for Ramp {
+    fn distance_to_point(&self, point: Vec2) -> f32 {
+        let (points, normals, _lengths) = get_polygon_data_for_ramp(self.direction, self.length);
+        distance_to_polygon(&points, &normals, point)
+    }
+}
+
 impl RayTarget for Ramp {
     fn raycast(&self, origin: Vec2, ray: &RayCaster) -> Option<[RayIntersection; 2]> {
         let (points, normals, lengths) = get_polygon_data_for_ramp(self.direction, self.length);
@@ -64,4 +72,43 @@ impl ShapeDebug for Ramp {
             Box::new(normals),
         )
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::Vec2;
+
+    use crate::prelude::{get_polygon_data_for_ramp, get_polygon_data_for_ramp_boxy, RampBoxy, RampBoxyRound, RampRound, ShapeCommon};
+
+    use super::Ramp;
+
+    /// The bounds of the points, grown by the radius
+    fn bounds_of(points: &[Vec2], radius: f32) -> [Vec2; 2] {
+        [
+            points.iter().fold(Vec2::MAX, |p, &c| p.min(c)) - radius,
+            points.iter().fold(Vec2::MIN, |p, &c| p.max(c)) + radius,
+        ]
+    }
+
+    #[test]
+    fn bounding_boxes_fit_the_ramp_points() {
+        let (radius, rect) = (0.25, Vec2::splat(0.5));
+        for size in [Vec2::new(2.0, 1.0), Vec2::new(-2.0, 1.0), Vec2::new(2.0, -1.0), Vec2::new(-2.0, -1.0)] {
+            let (direction, length) = (Vec2::new(size.x, -size.y).normalize(), size.length());
+            let (ramp, _, _) = get_polygon_data_for_ramp(direction, length);
+            let (boxy, _, _) = get_polygon_data_for_ramp_boxy(direction, length, rect);
+
+            let cases = [
+                ("Ramp",          Ramp::new(direction, length).bounding_box(),                        bounds_of(&ramp, 0.0)),
+                ("RampRound",     RampRound::new(direction, length, radius).bounding_box(),           bounds_of(&ramp, radius)),
+                ("RampBoxy",      RampBoxy::new(direction, length, rect).bounding_box(),              bounds_of(&boxy, 0.0)),
+                ("RampBoxyRound", RampBoxyRound::new(direction, length, rect, radius).bounding_box(), bounds_of(&boxy, radius)),
+            ];
+
+            for (name, [min, max], [expected_min, expected_max]) in cases {
+                assert!(min.abs_diff_eq(expected_min, 1e-5), "{name} {size}: min {min} != {expected_min}");
+                assert!(max.abs_diff_eq(expected_max, 1e-5), "{name} {size}: max {max} != {expected_max}");
+            }
+        }
+    }
+}
@@ -2,7 +2,7 @@
 
 use bevy::prelude::Vec2;
 
-use crate::prelude::{RayTarget, RayCaster, RayIntersection, ShapeDebug, ShapeDebugData, get_polygon_data_for_ramp_boxy, PolygonSmall, ShapeCommon};
+use crate::prelude::{RayTarget, RayCaster, RayIntersection, ShapeDebug, ShapeDebugData, get_polygon_data_for_ramp_boxy, PolygonSmall, ShapeCommon, ShapeDistance};
 
 #[derive(Debug, Clone, Copy)]
 pub struct RampBoxy(PolygonSmall);
@@ -11,7 +11,7 @@ impl RampBoxy {
     #[must_use]
     pub fn new(direction: Vec2, length: f32, size: Vec2) -> Self {
         let (points, normals, lengths) = get_polygon_data_for_ramp_boxy(direction, length, size);
-        let (min, max) = points.iter().fold((Vec2::MAX, Vec2::MIN), |p, &c| (p.0.min(c), p.0.max(c)));
+        let (min, max) = points.iter().fold((Vec2::MAX, Vec2::MIN), |p, &c| (p.0.min(c), p.1.max(c)));
 
         Self(PolygonSmall::new(points, normals, lengths, [min, max]))
     }
@@ -23,6 +23,12 @@ impl ShapeCommon for RampBoxy {
     }
 }
 
+impl ShapeDistance for RampBoxy {
+    fn distance_to_point(&self, point: Vec2) -> f32 {
+        self.0.distance_to_point(point)
+    }
+}
+
 impl RayTarget for RampBoxy {
     fn raycast(&self, origin: Vec2, ray: &RayCaster) -> Option<[RayIntersection; 2]> {
         self.0.raycast(origin, ray)
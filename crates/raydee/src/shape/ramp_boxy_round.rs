@@ -2,7 +2,7 @@
 
 use bevy::prelude::Vec2;
 
-use crate::prelude::{RayTarget, RayCaster, RayIntersection, ShapeDebugData, ShapeDebug, get_polygon_data_for_ramp_boxy, PolygonSmallRound, PolygonSmall, ShapeCommon};
+use crate::prelude::{RayTarget, RayCaster, RayIntersection, ShapeDebugData, ShapeDebug, get_polygon_data_for_ramp_boxy, PolygonSmallRound, PolygonSmall, ShapeCommon, ShapeDistance};
 
 #[derive(Debug, Clone, Copy)]
 pub struct RampBoxyRound(PolygonSmallRound);
@@ -12,7 +12,7 @@ impl RampBoxyRound {
     pub fn new(direction: Vec2, length: f32, size: Vec2, radius: f32) -> Self {
         let (points, normals, lengths) = get_polygon_data_for_ramp_boxy(direction, length, size);
 
-        let (min, max) = points.iter().fold((Vec2::MAX, Vec2::MIN), |p, &c| (p.0.min(c), p.0.max(c)));
+        let (min, max) = points.iter().fold((Vec2::MAX, Vec2::MIN), |p, &c| (p.0.min(c), p.1.max(c)));
 
         Self(PolygonSmallRound::new(PolygonSmall::new(points, normals, lengths, [min, max]), radius))
     }
@@ -24,6 +24,12 @@ impl ShapeCommon for RampBoxyRound {
     }
 }
 
+impl ShapeDistance for RampBoxyRound {
+    fn distance_to_point(&self, point: Vec2) -> f32 {
+        self.0.distance_to_point(point)
+    }
+}
+
 impl RayTarget for RampBoxyRound {
     fn raycast(&self, origin: Vec2, ray: &RayCaster) -> Option<[RayIntersection; 2]> {
         self.0.raycast(origin, ray)
@@ -1,10 +1,11 @@
 // Copyright 2023 Natalie Baker // AGPLv3 //
 
 use bevy::prelude::Vec2;
+use serde::Serialize;
 
-use crate::prelude::{RayTarget, RayCaster, RayIntersection, ShapeDebug, ShapeDebugData, get_polygon_data_for_ramp, ShapeCommon};
+use crate::prelude::{RayTarget, RayCaster, RayIntersection, ShapeDebug, ShapeDebugData, get_polygon_data_for_ramp, ShapeCommon, ShapeDistance, distance_to_polygon};
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize)]
 pub struct RampRound {
     pub direction: Vec2,
     pub length:    f32,
@@ -31,14 +32,21 @@ impl RampRound {
 impl ShapeCommon for RampRound {
     fn bounding_box(&self) -> [Vec2; 2] {
         let h_size = Vec2::new(self.direction.x, -self.direction.y)*self.length*0.5;
-        let origin = h_size+h_size.signum()*self.radius;
+        let extent = h_size.abs() + self.radius;
         [
-            origin + (h_size.abs() + self.radius),
-            origin - (h_size.abs() + self.radius),
+            h_size - extent,
+            h_size + extent,
         ]
     }
 }
 
+impl ShapeDistance for RampRound {
+    fn distance_to_point(&self, point: Vec2) -> f32 {
+        let (points, normals, _lengths) = get_polygon_data_for_ramp(self.direction, self.length);
+        (distance_to_polygon(&points, &normals, point) - self.radius).max(0.0)
+    }
+}
+
 impl RayTarget for RampRound {
     fn raycast(&self, origin: Vec2, ray: &RayCaster) -> Option<[RayIntersection; 2]> {
         let (points, normals, lengths) = get_polygon_data_for_ramp(self.direction, self.length);
@@ -40,6 +40,22 @@ impl ShapeCommon for ShapeCombined {
     }
 }
 
+impl ShapeDistance for ShapeCombined {
+    fn distance_to_point(&self, point: Vec2) -> f32 {
+        match self {
+            ShapeCombined::Circle(s) => s.distance_to_point(point),
+            ShapeCombined::Rectangle(s) => s.distance_to_point(point),
+            ShapeCombined::RectangleRound(s) => s.distance_to_point(point),
+            ShapeCombined::BoxOrientedRound(s) => s.distance_to_point(point),
+            ShapeCombined::BoxOrientedBoxy(s) => s.distance_to_point(point),
+            ShapeCombined::BoxOrientedBoxyRound(s) => s.distance_to_point(point),
+            ShapeCombined::RampRound(s) => s.distance_to_point(point),
+            ShapeCombined::RampBoxy(s) => s.distance_to_point(point),
+            ShapeCombined::RampBoxyRound(s) => s.distance_to_point(point),
+        }
+    }
+}
+
 impl ShapeDebug for ShapeCombined {
     fn get_debug_shape_data(&self) -> ShapeDebugData {
         match self {
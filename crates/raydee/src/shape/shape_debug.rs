@@ -1,5 +1,7 @@
 // Copyright 2023 Natalie Baker // AGPLv3 //
 
+use core::f32::consts::TAU;
+
 use bevy::prelude::Vec2;
 
 pub enum ShapeDebugData {
@@ -49,6 +51,33 @@ impl ShapeDebugData {
         })
     }
 
+    #[must_use]
+    pub fn outline(&self, segments: u32) -> Vec<Vec2> {
+        let segments = segments.max(3);
+        match self {
+            ShapeDebugData::Circle { radius } => (0..segments)
+                .map(|i| Vec2::from_angle(TAU * (i as f32)/(segments as f32)) * *radius)
+                .collect(),
+            ShapeDebugData::Polygon { points, .. } => points.to_vec(),
+            ShapeDebugData::PolygonRound { points, radius, .. } if *radius <= 0.0 => points.to_vec(),
+            ShapeDebugData::PolygonRound { points, normals, radius } => {
+                // Each corner gets an arc from the previous edge normal to the next
+                let mut result = Vec::with_capacity(points.len()*2);
+                for i in 0..points.len() {
+                    let norm_in  = normals[(i + points.len() - 1) % points.len()];
+                    let norm_out = normals[i];
+                    let angle = norm_in.perp_dot(norm_out).atan2(norm_in.dot(norm_out)).max(0.0);
+                    let steps = ((angle/TAU) * (segments as f32)).ceil().max(1.0) as u32;
+                    result.extend((0..=steps).map(|s| {
+                        let dir = norm_in.rotate(Vec2::from_angle(angle * (s as f32)/(steps as f32)));
+                        points[i] + dir * *radius
+                    }));
+                }
+                result
+            },
+        }
+    }
+
 }
 
 pub trait ShapeDebug {
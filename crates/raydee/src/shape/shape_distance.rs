@@ -0,0 +1,9 @@
+// Copyright 2025 Natalie Baker // AGPLv3 //
+
+use bevy::math::Vec2;
+
+/// Shapes that can measure how far away a point is, ie. for nearest queries
+pub trait ShapeDistance {
+    /// Distance from a point, relative to the shape's origin, to the shape's edge. Zero when inside.
+    fn distance_to_point(&self, point: Vec2) -> f32;
+}
@@ -3,11 +3,12 @@
 use bevy::math::{primitives::{Circle, Rectangle}, Vec2};
 use macro_attr_2018::macro_attr;
 use enum_derive_2018::EnumFromInner;
+use serde::Serialize;
 
 use crate::prelude::*;
 
 macro_attr! {
-    #[derive(EnumFromInner!, Debug, Clone, Copy)]
+    #[derive(EnumFromInner!, Debug, Clone, Copy, PartialEq, Serialize)]
     pub enum ShapeMoving {
         Circle(Circle),
         Rectangle(Rectangle),
@@ -34,6 +35,27 @@ impl ShapeMoving {
 
 }
 
+impl From<ShapeMoving> for ShapeStatic {
+    fn from(value: ShapeMoving) -> Self {
+        match value {
+            ShapeMoving::Circle(s)    => ShapeStatic::Circle(s),
+            ShapeMoving::Rectangle(s) => ShapeStatic::Rectangle(s),
+        }
+    }
+}
+
+impl TryFrom<ShapeStatic> for ShapeMoving {
+    type Error = ShapeStatic;
+
+    fn try_from(value: ShapeStatic) -> Result<Self, Self::Error> {
+        match value {
+            ShapeStatic::Circle(s)    => Ok(ShapeMoving::Circle(s)),
+            ShapeStatic::Rectangle(s) => Ok(ShapeMoving::Rectangle(s)),
+            other => Err(other),
+        }
+    }
+}
+
 impl ShapeCommon for ShapeMoving {
     fn bounding_box(&self) -> [Vec2; 2] {
         match self {
@@ -43,6 +65,15 @@ impl ShapeCommon for ShapeMoving {
     }
 }
 
+impl ShapeDistance for ShapeMoving {
+    fn distance_to_point(&self, point: Vec2) -> f32 {
+        match self {
+            ShapeMoving::Circle(s) => s.distance_to_point(point),
+            ShapeMoving::Rectangle(s) => s.distance_to_point(point),
+        }
+    }
+}
+
 impl ShapeDebug for ShapeMoving {
     fn get_debug_shape_data(&self) -> ShapeDebugData {
         match self {
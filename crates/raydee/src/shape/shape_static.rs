@@ -1,13 +1,14 @@
 // Copyright 2023 Natalie Baker // AGPLv3 //
 
-use bevy::math::{primitives::{Circle, Rectangle}, Vec2};
+use bevy::math::{primitives::{Circle, Rectangle}, BVec2, Vec2};
 use macro_attr_2018::macro_attr;
 use enum_derive_2018::EnumFromInner;
+use serde::Serialize;
 
 use crate::prelude::*;
 
 macro_attr! {
-    #[derive(EnumFromInner!, Debug, Copy, Clone)]
+    #[derive(EnumFromInner!, Debug, Copy, Clone, Serialize)]
     pub enum ShapeStatic {
         Circle(Circle),
         Rectangle(Rectangle),
@@ -19,6 +20,57 @@ macro_attr! {
     }
 }
 
+impl ShapeStatic {
+
+    /// Mirrors the shape about its origin on the selected axes, ie. for flipped tiles
+    #[must_use]
+    pub fn mirrored(self, axes: BVec2) -> Self {
+        let sign = Vec2::select(axes, Vec2::NEG_ONE, Vec2::ONE);
+        match self {
+            ShapeStatic::Circle(_) | ShapeStatic::Rectangle(_) | ShapeStatic::BoxAlignedRound(_) => self,
+            ShapeStatic::BoxOriented(s)      => ShapeStatic::BoxOriented(BoxOriented::new(s.size, s.direction*sign)),
+            ShapeStatic::BoxOrientedRound(s) => ShapeStatic::BoxOrientedRound(BoxOrientedRound::new(s.size, s.direction*sign, s.radius)),
+            ShapeStatic::Ramp(s)             => ShapeStatic::Ramp(Ramp::new(s.direction*sign, s.length)),
+            ShapeStatic::RampRound(s)        => ShapeStatic::RampRound(RampRound::new(s.direction*sign, s.length, s.radius)),
+        }
+    }
+
+    /// Corners of the shape relative to its origin, which rounded shapes sweep a circle of
+    /// [`ShapeStatic::rounding`] around. A circle is only its center.
+    #[must_use]
+    pub fn vertices(&self) -> Vec<Vec2> {
+        let rectangle = |size: Vec2, direction: Vec2| [
+            Vec2::new( size.x,  size.y).rotate(direction),
+            Vec2::new(-size.x,  size.y).rotate(direction),
+            Vec2::new(-size.x, -size.y).rotate(direction),
+            Vec2::new( size.x, -size.y).rotate(direction),
+        ].to_vec();
+
+        match self {
+            ShapeStatic::Circle(_)           => vec![Vec2::ZERO],
+            ShapeStatic::Rectangle(s)        => rectangle(s.half_size, Vec2::X),
+            ShapeStatic::BoxAlignedRound(s)  => rectangle(s.inner.half_size, Vec2::X),
+            ShapeStatic::BoxOriented(s)      => rectangle(s.size, s.direction),
+            ShapeStatic::BoxOrientedRound(s) => rectangle(s.size, s.direction),
+            ShapeStatic::Ramp(s)             => get_polygon_data_for_ramp(s.direction, s.length).0.to_vec(),
+            ShapeStatic::RampRound(s)        => get_polygon_data_for_ramp(s.direction, s.length).0.to_vec(),
+        }
+    }
+
+    /// Radius of the circle swept around each of the [`ShapeStatic::vertices`], zero for sharp corners
+    #[must_use]
+    pub const fn rounding(&self) -> f32 {
+        match self {
+            ShapeStatic::Circle(s)           => s.radius,
+            ShapeStatic::BoxAlignedRound(s)  => s.radius,
+            ShapeStatic::BoxOrientedRound(s) => s.radius,
+            ShapeStatic::RampRound(s)        => s.radius,
+            ShapeStatic::Rectangle(_) | ShapeStatic::BoxOriented(_) | ShapeStatic::Ramp(_) => 0.0,
+        }
+    }
+
+}
+
 impl ShapeCommon for ShapeStatic {
     fn bounding_box(&self) -> [Vec2; 2] {
         match self {
@@ -33,6 +85,20 @@ impl ShapeCommon for ShapeStatic {
     }
 }
 
+impl ShapeDistance for ShapeStatic {
+    fn distance_to_point(&self, point: Vec2) -> f32 {
+        match self {
+            ShapeStatic::Circle(s) => s.distance_to_point(point),
+            ShapeStatic::Rectangle(s) => s.distance_to_point(point),
+            ShapeStatic::BoxAlignedRound(s) => s.distance_to_point(point),
+            ShapeStatic::BoxOriented(s) => s.distance_to_point(point),
+            ShapeStatic::BoxOrientedRound(s) => s.distance_to_point(point),
+            ShapeStatic::Ramp(s) => s.distance_to_point(point),
+            ShapeStatic::RampRound(s) => s.distance_to_point(point),
+        }
+    }
+}
+
 impl ShapeDebug for ShapeStatic {
     fn get_debug_shape_data(&self) -> ShapeDebugData {
         match self {
@@ -138,4 +138,22 @@ fn get_polygon_data_for_oriented_rect_rected_quick_impl(points: &[Vec2; 4], norm
     }
 
     result
-}
\ No newline at end of file
+}
+
+pub(crate) fn distance_to_rect(half_size: Vec2, point: Vec2) -> f32 {
+    (point.abs() - half_size).max(Vec2::ZERO).length()
+}
+
+pub(crate) fn distance_to_polygon(points: &[Vec2], normals: &[Vec2], point: Vec2) -> f32 {
+    // Polygons are convex, so a point behind every edge is inside
+    if points.iter().zip(normals).all(|(&from, normal)| normal.dot(point - from) <= 0.0) {
+        return 0.0;
+    }
+
+    (0..points.len()).map(|i| {
+        let from = points[i];
+        let edge = points[(i+1) % points.len()] - from;
+        let t    = ((point - from).dot(edge) / edge.length_squared().max(f32::EPSILON)).clamp(0.0, 1.0);
+        point.distance(from + edge*t)
+    }).fold(f32::INFINITY, f32::min)
+}